@@ -1,40 +1,17 @@
-use crate::common::{Board, Direction};
+use crate::common::{Board, Direction, Heuristic, SearchStats};
 use crate::{SolutionInfo, Solver};
 use std::cmp::Ordering;
 use std::collections::{BinaryHeap, HashSet};
 
 #[derive(Clone, Eq)]
 struct State {
-    state: Vec<u8>,
-    goal_state: Vec<u8>,
-    blank_pos: usize,
-    size: usize,
+    board: Board,
     path: Vec<Direction>,
     g_cost: u32, // Cost from start to current node
-    h_cost: u32, // Heuristic cost (Manhattan distance)
+    h_cost: u32, // Heuristic cost
 }
 
 impl State {
-    fn new(board: &Board) -> Self {
-        let state: Vec<u8> = board
-            .get_state()
-            .into_iter()
-            .flat_map(|row| row.into_iter())
-            .collect();
-        let goal_state = board.get_goal_state();
-        let size = board.get_size();
-        let h_cost = AStarSolver::manhattan_distance(&state, &goal_state, size);
-        State {
-            state,
-            goal_state,
-            blank_pos: 0, // Will be set in initialization
-            size,
-            path: Vec::new(),
-            g_cost: 0,
-            h_cost,
-        }
-    }
-
     fn f_cost(&self) -> u32 {
         self.g_cost + self.h_cost
     }
@@ -64,88 +41,33 @@ impl PartialEq for State {
 }
 
 impl State {
-    fn is_goal(&self) -> bool {
-        self.state == self.goal_state
-    }
-
-    fn get_possible_moves(&self) -> Vec<Direction> {
-        let mut moves = Vec::new();
-        let row = self.blank_pos / self.size;
-        let col = self.blank_pos % self.size;
-
-        if row > 0 {
-            moves.push(Direction::Up);
-        }
-        if row < self.size - 1 {
-            moves.push(Direction::Down);
-        }
-        if col > 0 {
-            moves.push(Direction::Left);
-        }
-        if col < self.size - 1 {
-            moves.push(Direction::Right);
-        }
-
-        moves
-    }
-
-    fn make_move(&self, dir: Direction) -> Option<State> {
-        let row = self.blank_pos / self.size;
-        let col = self.blank_pos % self.size;
-
-        let new_pos = match dir {
-            Direction::Up if row > 0 => self.blank_pos - self.size,
-            Direction::Down if row < self.size - 1 => self.blank_pos + self.size,
-            Direction::Left if col > 0 => self.blank_pos - 1,
-            Direction::Right if col < self.size - 1 => self.blank_pos + 1,
-            _ => return None,
-        };
-
-        let mut new_state = self.state.clone();
-        new_state.swap(self.blank_pos, new_pos);
+    fn make_move(&self, dir: Direction, heuristic: Heuristic) -> Option<State> {
+        let move_cost = self.board.cost_of_move(dir)?;
+        let mut board = self.board.clone();
+        board.make_move(dir).ok()?;
 
         let mut new_path = self.path.clone();
         new_path.push(dir);
-
-        let new_h_cost = AStarSolver::manhattan_distance(&new_state, &self.goal_state, self.size);
+        let h_cost = board.heuristic(heuristic);
 
         Some(State {
-            state: new_state,
-            goal_state: self.goal_state.clone(),
-            blank_pos: new_pos,
-            size: self.size,
+            board,
             path: new_path,
-            g_cost: self.g_cost + 1, // Increment cost by 1 for each move
-            h_cost: new_h_cost,
+            g_cost: self.g_cost + move_cost, // Pay for the tile that slid
+            h_cost,
         })
     }
 }
 
 pub struct AStarSolver {
     initial_board: Board,
+    heuristic: Heuristic,
 }
 
 impl AStarSolver {
-    fn find_blank_pos(state: &[u8]) -> usize {
-        state.iter().position(|&x| x == 0).unwrap_or(0)
-    }
-
-    fn manhattan_distance(state: &[u8], goal_state: &[u8], size: usize) -> u32 {
-        let mut distance = 0;
-        for pos in 0..state.len() {
-            let value = state[pos];
-            if value != 0 {
-                if let Some(goal_pos) = goal_state.iter().position(|&x| x == value) {
-                    let current_row = pos / size;
-                    let current_col = pos % size;
-                    let goal_row = goal_pos / size;
-                    let goal_col = goal_pos % size;
-                    distance +=
-                        (current_row.abs_diff(goal_row) + current_col.abs_diff(goal_col)) as u32;
-                }
-            }
-        }
-        distance
+    pub fn with_heuristic(mut self, heuristic: Heuristic) -> Self {
+        self.heuristic = heuristic;
+        self
     }
 }
 
@@ -153,59 +75,61 @@ impl Solver for AStarSolver {
     fn new(initial: Board) -> Self {
         AStarSolver {
             initial_board: initial,
+            heuristic: Heuristic::default(),
         }
     }
 
     fn new_with_goal(initial: Board) -> Self {
         AStarSolver {
             initial_board: initial,
+            heuristic: Heuristic::default(),
         }
     }
 
-    fn solve(&self, _optimal_length: Option<usize>) -> Option<SolutionInfo> {
+    fn solve(&self, optimal_length: Option<usize>) -> Option<SolutionInfo> {
         // Initialize start state
-        let state: Vec<u8> = self
-            .initial_board
-            .get_state()
-            .into_iter()
-            .flat_map(|row| row.into_iter())
-            .collect();
-        let goal_state = self.initial_board.get_goal_state();
-
         let initial_state = State {
-            state: state.clone(),
-            goal_state: goal_state.clone(),
-            blank_pos: Self::find_blank_pos(&state),
-            size: self.initial_board.get_size(),
+            board: self.initial_board.clone(),
             path: Vec::new(),
             g_cost: 0,
-            h_cost: Self::manhattan_distance(&state, &goal_state, self.initial_board.get_size()),
+            h_cost: self.initial_board.heuristic(self.heuristic),
         };
 
         let mut open_set = BinaryHeap::new();
         let mut closed_set = HashSet::new();
+        let mut stats = SearchStats::default();
 
         open_set.push(initial_state);
 
         while let Some(current_state) = open_set.pop() {
             // Goal test
-            if current_state.is_goal() {
-                return Some(SolutionInfo::new(current_state.path, None));
+            if current_state.board.is_goal() {
+                let cost = current_state.g_cost;
+                let goal = current_state.board.reached_goal();
+                return Some(
+                    SolutionInfo::new(current_state.path, optimal_length)
+                        .with_cost(cost)
+                        .with_goal(goal)
+                        .with_stats(stats),
+                );
             }
 
             // Add current state to closed set
-            if !closed_set.insert(current_state.state.clone()) {
+            if !closed_set.insert(current_state.board.tiles().to_vec()) {
                 continue; // Skip if we've already explored this state
             }
+            stats.nodes_expanded += 1;
 
             // Generate and explore successors
-            for direction in current_state.get_possible_moves() {
-                if let Some(next_state) = current_state.make_move(direction) {
-                    if !closed_set.contains(&next_state.state) {
+            for direction in current_state.board.get_possible_moves() {
+                if let Some(next_state) = current_state.make_move(direction, self.heuristic) {
+                    if !closed_set.contains(next_state.board.tiles()) {
+                        stats.nodes_generated += 1;
                         open_set.push(next_state);
                     }
                 }
             }
+            stats.max_frontier = stats.max_frontier.max(open_set.len());
         }
 
         None // No solution found
@@ -261,7 +185,7 @@ mod tests {
     #[test]
     fn test_solution_validity() {
         // Test if solution reaches the goal state
-        let initial_state = vec![vec![1, 0], vec![2, 3]];
+        let initial_state = vec![vec![0, 1], vec![3, 2]];
         let board = Board::new(initial_state);
         let solver = AStarSolver::new(board.clone());
         let solution = solver.solve(None).unwrap();
@@ -280,4 +204,39 @@ mod tests {
         let expected = vec![vec![1, 2], vec![3, 0]];
         assert_eq!(final_state, expected, "Final state should match goal state");
     }
+
+    #[test]
+    fn test_wildcard_goal() {
+        // Only the first row has to be 1 2 3
+        let initial_state = vec![vec![8, 6, 7], vec![2, 5, 4], vec![3, 0, 1]];
+        let goal = vec![vec![1, 2, 3], vec![-1, -1, -1], vec![-1, -1, -1]];
+        let board = Board::new_with_goal(initial_state.clone(), goal);
+        let full = AStarSolver::new(Board::new(initial_state))
+            .solve(None)
+            .unwrap();
+        let partial = AStarSolver::new(board.clone()).solve(None).unwrap();
+
+        let mut test_board = board;
+        for dir in partial.moves.iter() {
+            test_board.make_move(*dir).unwrap();
+        }
+        assert!(test_board.is_goal());
+        assert_eq!(test_board.get_row(0), vec![1, 2, 3]);
+        assert!(partial.moves.len() < full.moves.len());
+    }
+
+    #[test]
+    fn test_reports_nearest_of_several_goals() {
+        let snail = Board::snail_goal(3);
+        assert_eq!(snail, vec![vec![1, 2, 3], vec![8, 0, 4], vec![7, 6, 5]]);
+
+        let standard = vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 0]];
+        let initial_state = vec![vec![1, 2, 3], vec![8, 4, 0], vec![7, 6, 5]];
+        let board = Board::new_with_goals(initial_state, vec![standard, snail]);
+        assert_eq!(board.heuristic(Heuristic::Manhattan), 1);
+
+        let solution = AStarSolver::new(board).solve(None).unwrap();
+        assert_eq!(solution.moves, vec![Direction::Left]);
+        assert_eq!(solution.reached_goal, Some(1));
+    }
 }
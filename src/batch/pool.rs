@@ -0,0 +1,181 @@
+use serde_json::{json, Value};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::common::input::{solution_to_json, PuzzleEntry};
+use crate::{SolutionInfo, SolverKind, SolverOptions};
+
+/// Result for one puzzle of a batch.
+#[derive(Debug, Clone)]
+pub struct BatchRecord {
+    /// Position of the puzzle in the input
+    pub index: usize,
+    pub id: Option<Value>,
+    /// The solution, or why there is none
+    pub outcome: Result<SolutionInfo, String>,
+    pub time_taken: Duration,
+}
+
+impl BatchRecord {
+    pub fn is_solved(&self) -> bool {
+        self.outcome.is_ok()
+    }
+
+    /// One line of the JSON Lines output.
+    pub fn to_json(&self) -> Value {
+        let mut record = match &self.outcome {
+            Ok(solution) => solution_to_json(solution),
+            Err(_) => json!({}),
+        };
+        record["index"] = json!(self.index);
+        if let Some(id) = &self.id {
+            record["id"] = id.clone();
+        }
+        record["solved"] = json!(self.is_solved());
+        if let Err(reason) = &self.outcome {
+            record["error"] = json!(reason);
+        }
+        record["time_ms"] = json!(self.time_taken.as_secs_f64() * 1000.0);
+        record
+    }
+}
+
+/// Solves many puzzles with one solver on a pool of worker threads. Every
+/// puzzle gets a record, in input order, whether or not it was solved.
+pub struct BatchSolver {
+    kind: SolverKind,
+    options: SolverOptions,
+    threads: usize,
+}
+
+impl BatchSolver {
+    pub fn new(kind: SolverKind) -> Self {
+        BatchSolver {
+            kind,
+            options: SolverOptions::default(),
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+        }
+    }
+
+    pub fn with_options(mut self, options: SolverOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    fn solve_one(&self, index: usize, entry: &PuzzleEntry) -> BatchRecord {
+        let started = Instant::now();
+        let outcome = match &entry.board {
+            Err(reason) => Err(reason.clone()),
+            // Exhaustive solvers would search the whole space before giving up
+            Ok(board) if !board.is_solvable() => Err("unsolvable".to_string()),
+            Ok(board) => {
+                let solve = || self.kind.solve(board, &self.options);
+                match panic::catch_unwind(AssertUnwindSafe(solve)) {
+                    Ok(Some(solution)) => Ok(solution),
+                    Ok(None) => Err("no solution found".to_string()),
+                    Err(_) => Err("solver panicked".to_string()),
+                }
+            }
+        };
+
+        BatchRecord {
+            index,
+            id: entry.id.clone(),
+            outcome,
+            time_taken: started.elapsed(),
+        }
+    }
+
+    pub fn solve_all(&self, puzzles: &[PuzzleEntry]) -> Vec<BatchRecord> {
+        let next = AtomicUsize::new(0);
+        let records: Vec<Mutex<Option<BatchRecord>>> =
+            puzzles.iter().map(|_| Mutex::new(None)).collect();
+
+        thread::scope(|scope| {
+            for _ in 0..self.threads.min(puzzles.len()) {
+                scope.spawn(|| loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(entry) = puzzles.get(index) else {
+                        break;
+                    };
+                    let record = self.solve_one(index, entry);
+                    *records[index].lock().unwrap() = Some(record);
+                });
+            }
+        });
+
+        records
+            .into_iter()
+            .map(|slot| {
+                slot.into_inner()
+                    .unwrap()
+                    .expect("every puzzle is solved once")
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Board;
+
+    fn entry(grid: Vec<Vec<i32>>) -> PuzzleEntry {
+        PuzzleEntry {
+            id: None,
+            board: Ok(Board::new(grid)),
+        }
+    }
+
+    #[test]
+    fn test_records_keep_input_order() {
+        let puzzles = vec![
+            entry(vec![vec![8, 6, 7], vec![2, 5, 4], vec![3, 0, 1]]),
+            entry(vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 0, 8]]),
+            entry(vec![vec![1, 2, 3], vec![4, 0, 6], vec![7, 5, 8]]),
+            entry(vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 0]]),
+        ];
+        let records = BatchSolver::new(SolverKind::AStar)
+            .with_threads(3)
+            .solve_all(&puzzles);
+
+        let lengths: Vec<usize> = records
+            .iter()
+            .map(|record| record.outcome.as_ref().unwrap().moves.len())
+            .collect();
+        assert_eq!(lengths, [31, 1, 2, 0]);
+        assert!(records.iter().enumerate().all(|(i, r)| r.index == i));
+    }
+
+    #[test]
+    fn test_failures_do_not_stop_the_batch() {
+        let puzzles = vec![
+            PuzzleEntry {
+                id: Some(json!("broken")),
+                board: Err("missing board field".to_string()),
+            },
+            entry(vec![vec![2, 1, 3], vec![4, 5, 6], vec![7, 8, 0]]),
+            entry(vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 0, 8]]),
+        ];
+        let records = BatchSolver::new(SolverKind::IdaStar)
+            .with_threads(2)
+            .solve_all(&puzzles);
+
+        assert_eq!(
+            records[0].outcome.as_ref().unwrap_err(),
+            "missing board field"
+        );
+        assert_eq!(records[0].to_json()["id"], "broken");
+        assert_eq!(records[1].outcome.as_ref().unwrap_err(), "unsolvable");
+        assert!(records[2].is_solved());
+        assert_eq!(records[2].to_json()["moves"], "R");
+    }
+}
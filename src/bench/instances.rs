@@ -0,0 +1,100 @@
+use crate::Board;
+
+/// One puzzle of a benchmark, with its optimal solution length when known.
+#[derive(Clone, Debug)]
+pub struct Instance {
+    /// Number of the instance within its set, starting at 1
+    pub id: usize,
+    pub board: Board,
+    pub optimal_length: Option<usize>,
+}
+
+// Spread over every distance from 8 to 31 moves, including both 31-move
+// positions; standard goal with the blank last
+const EIGHT_PUZZLE: [(usize, [i32; 9]); 19] = [
+    (8, [1, 2, 3, 6, 0, 8, 4, 7, 5]),
+    (10, [2, 5, 3, 4, 0, 6, 7, 1, 8]),
+    (12, [2, 3, 6, 5, 0, 8, 1, 4, 7]),
+    (14, [0, 5, 1, 4, 6, 3, 7, 2, 8]),
+    (16, [1, 6, 3, 4, 7, 8, 2, 5, 0]),
+    (18, [0, 3, 4, 7, 2, 1, 5, 8, 6]),
+    (20, [0, 7, 2, 6, 1, 8, 4, 5, 3]),
+    (21, [2, 8, 5, 0, 6, 3, 7, 1, 4]),
+    (22, [2, 5, 6, 7, 4, 8, 0, 1, 3]),
+    (23, [7, 5, 1, 6, 3, 0, 4, 8, 2]),
+    (24, [2, 3, 7, 8, 1, 5, 6, 4, 0]),
+    (25, [5, 1, 2, 0, 6, 4, 3, 8, 7]),
+    (26, [6, 7, 8, 5, 0, 3, 1, 4, 2]),
+    (27, [1, 8, 4, 0, 5, 2, 3, 6, 7]),
+    (28, [5, 6, 3, 8, 4, 2, 0, 7, 1]),
+    (29, [7, 5, 8, 1, 2, 4, 3, 0, 6]),
+    (30, [3, 8, 2, 6, 5, 4, 0, 7, 1]),
+    (31, [8, 6, 7, 2, 5, 4, 3, 0, 1]),
+    (31, [6, 4, 7, 8, 5, 0, 3, 2, 1]),
+];
+
+const KORF_100: &str = include_str!("korf100.txt");
+
+/// The bundled instance sets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InstanceSet {
+    /// A fixed spread of 8-puzzles, quick enough for every solver
+    EightPuzzle,
+    /// Korf's 100 random 15-puzzles, goal with the blank first
+    Korf100,
+}
+
+impl InstanceSet {
+    pub const ALL: [InstanceSet; 2] = [InstanceSet::EightPuzzle, InstanceSet::Korf100];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            InstanceSet::EightPuzzle => "8-puzzle",
+            InstanceSet::Korf100 => "korf100",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<InstanceSet> {
+        Self::ALL.into_iter().find(|set| set.name() == name)
+    }
+
+    pub fn instances(&self) -> Vec<Instance> {
+        match self {
+            InstanceSet::EightPuzzle => EIGHT_PUZZLE
+                .iter()
+                .enumerate()
+                .map(|(i, (optimal_length, tiles))| Instance {
+                    id: i + 1,
+                    board: Board::new(grid(tiles, 3)),
+                    optimal_length: Some(*optimal_length),
+                })
+                .collect(),
+            InstanceSet::Korf100 => parse_korf(KORF_100),
+        }
+    }
+}
+
+fn grid(tiles: &[i32], size: usize) -> Vec<Vec<i32>> {
+    tiles.chunks(size).map(<[i32]>::to_vec).collect()
+}
+
+// "number optimal t0 .. t15" per line, '#' starts a comment
+fn parse_korf(text: &str) -> Vec<Instance> {
+    let goal: Vec<i32> = (0..16).collect();
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let numbers: Vec<usize> = line
+                .split_whitespace()
+                .map(|n| n.parse().expect("korf100.txt holds numbers only"))
+                .collect();
+            let tiles: Vec<i32> = numbers[2..].iter().map(|&n| n as i32).collect();
+            Instance {
+                id: numbers[0],
+                board: Board::new_with_goal(grid(&tiles, 4), grid(&goal, 4)),
+                optimal_length: Some(numbers[1]),
+            }
+        })
+        .collect()
+}
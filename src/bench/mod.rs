@@ -0,0 +1,4 @@
+mod instances;
+mod suite;
+pub use instances::{Instance, InstanceSet};
+pub use suite::{BenchRow, Benchmark};
@@ -0,0 +1,177 @@
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+
+use super::Instance;
+use crate::{Heuristic, SolverKind, SolverOptions};
+
+/// How one solver, with one heuristic, fared over every instance of a run.
+#[derive(Clone, Debug)]
+pub struct BenchRow {
+    pub solver: SolverKind,
+    /// `None` for solvers that take no heuristic
+    pub heuristic: Option<Heuristic>,
+    pub instances: usize,
+    pub solved: usize,
+    /// Solutions no longer than the instance's optimum, where it is known
+    pub optimal: usize,
+    /// Median over the instances of each instance's median run time
+    pub median_time: Duration,
+    /// Sum of the per-instance medians
+    pub total_time: Duration,
+    /// Summed over the solved instances
+    pub nodes_expanded: usize,
+    /// Moves, summed over the solved instances
+    pub total_length: usize,
+}
+
+impl BenchRow {
+    pub fn heuristic_name(&self) -> &'static str {
+        self.heuristic.map_or("-", |heuristic| heuristic.name())
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "solver": self.solver.name(),
+            "heuristic": self.heuristic.map(|heuristic| heuristic.name()),
+            "instances": self.instances,
+            "solved": self.solved,
+            "optimal": self.optimal,
+            "median_ms": self.median_time.as_secs_f64() * 1000.0,
+            "total_ms": self.total_time.as_secs_f64() * 1000.0,
+            "nodes_expanded": self.nodes_expanded,
+            "total_length": self.total_length,
+        })
+    }
+}
+
+/// Times solvers over a fixed list of instances. Each instance is solved
+/// `runs` times and only the median time is kept, so a stray slow run does
+/// not show up as a regression; node counts and lengths come from the last
+/// run, as they do not change between runs.
+pub struct Benchmark {
+    instances: Vec<Instance>,
+    options: SolverOptions,
+    runs: usize,
+}
+
+impl Benchmark {
+    pub fn new(instances: Vec<Instance>) -> Self {
+        Benchmark {
+            instances,
+            options: SolverOptions::default(),
+            runs: 5,
+        }
+    }
+
+    /// Settings for every run; the heuristic is overridden per row.
+    pub fn with_options(mut self, options: SolverOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn with_runs(mut self, runs: usize) -> Self {
+        self.runs = runs.max(1);
+        self
+    }
+
+    pub fn instances(&self) -> &[Instance] {
+        &self.instances
+    }
+
+    pub fn run(&self, solver: SolverKind, heuristic: Option<Heuristic>) -> BenchRow {
+        let mut options = self.options.clone();
+        if let Some(heuristic) = heuristic {
+            options.heuristic = heuristic;
+        }
+
+        let mut row = BenchRow {
+            solver,
+            heuristic,
+            instances: self.instances.len(),
+            solved: 0,
+            optimal: 0,
+            median_time: Duration::ZERO,
+            total_time: Duration::ZERO,
+            nodes_expanded: 0,
+            total_length: 0,
+        };
+        let mut medians = Vec::with_capacity(self.instances.len());
+        for instance in &self.instances {
+            let mut times = Vec::with_capacity(self.runs);
+            let mut last = None;
+            for _ in 0..self.runs {
+                let start = Instant::now();
+                last = solver.solve(&instance.board, &options);
+                times.push(start.elapsed());
+            }
+            times.sort();
+            medians.push(times[self.runs / 2]);
+
+            if let Some(solution) = last {
+                row.solved += 1;
+                if instance
+                    .optimal_length
+                    .is_some_and(|optimal| solution.moves.len() <= optimal)
+                {
+                    row.optimal += 1;
+                }
+                row.nodes_expanded += solution.stats.nodes_expanded;
+                row.total_length += solution.moves.len();
+            }
+        }
+
+        row.total_time = medians.iter().sum();
+        medians.sort();
+        row.median_time = medians.get(medians.len() / 2).copied().unwrap_or_default();
+        row
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::InstanceSet;
+    use crate::{Board, DistanceTable};
+
+    #[test]
+    fn test_eight_puzzle_lengths_are_optimal() {
+        let table = DistanceTable::build(&Board::new(Board::standard_goal(3)));
+        for instance in InstanceSet::EightPuzzle.instances() {
+            assert_eq!(
+                table.distance(&instance.board),
+                instance.optimal_length.map(|length| length as u8),
+                "instance {}",
+                instance.id
+            );
+        }
+    }
+
+    #[test]
+    fn test_korf_instances_are_well_formed() {
+        let instances = InstanceSet::Korf100.instances();
+        assert_eq!(instances.len(), 100);
+        for (i, instance) in instances.iter().enumerate() {
+            assert_eq!(instance.id, i + 1);
+            assert!(instance.board.is_solvable(), "instance {}", instance.id);
+            // Manhattan distance is a lower bound with the same parity
+            let optimal = instance.optimal_length.unwrap();
+            let manhattan = instance.board.heuristic(Heuristic::Manhattan) as usize;
+            assert!(manhattan <= optimal);
+            assert_eq!((optimal - manhattan) % 2, 0);
+        }
+    }
+
+    #[test]
+    fn test_row_sums_over_instances() {
+        let instances: Vec<Instance> = InstanceSet::EightPuzzle.instances()[..4].to_vec();
+        let total: usize = instances.iter().filter_map(|i| i.optimal_length).sum();
+        let row = Benchmark::new(instances)
+            .with_runs(3)
+            .run(SolverKind::AStar, Some(Heuristic::LinearConflict));
+
+        assert_eq!((row.instances, row.solved, row.optimal), (4, 4, 4));
+        assert_eq!(row.total_length, total);
+        assert!(row.nodes_expanded > 0);
+        assert!(row.median_time <= row.total_time);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::common::{Board, Direction};
+use crate::common::{matches_goal, Board, Direction, Tile};
 use crate::{SolutionInfo, Solver};
 use colored::*;
 use std::collections::{HashSet, VecDeque};
@@ -6,50 +6,45 @@ use std::collections::{HashSet, VecDeque};
 // State struct for BFS
 #[derive(Clone)]
 struct State {
-    state: Vec<u8>,
-    goal_state: Vec<u8>,
+    state: Vec<Tile>,
+    goal_states: Vec<Vec<Tile>>,
+    blocked: Vec<bool>,
     blank_pos: usize,
     size: usize,
     path: Vec<Direction>,
 }
 
 impl State {
+    // Distance to the nearest goal
     fn manhattan_distance(&self) -> u32 {
-        let mut distance = 0;
-        for pos in 0..self.state.len() {
-            let value = self.state[pos];
-            if value != 0 {
-                if let Some(goal_pos) = self.goal_state.iter().position(|&x| x == value) {
-                    let current_row = pos / self.size;
-                    let current_col = pos % self.size;
-                    let goal_row = goal_pos / self.size;
-                    let goal_col = goal_pos % self.size;
-                    distance +=
-                        (current_row.abs_diff(goal_row) + current_col.abs_diff(goal_col)) as u32;
+        self.goal_states
+            .iter()
+            .map(|goal_state| {
+                let mut distance = 0;
+                for pos in 0..self.state.len() {
+                    let value = self.state[pos];
+                    if value != 0 {
+                        if let Some(goal_pos) = goal_state.iter().position(|&x| x == value) {
+                            let current_row = pos / self.size;
+                            let current_col = pos % self.size;
+                            let goal_row = goal_pos / self.size;
+                            let goal_col = goal_pos % self.size;
+                            distance += (current_row.abs_diff(goal_row)
+                                + current_col.abs_diff(goal_col))
+                                as u32;
+                        }
+                    }
                 }
-            }
-        }
-        distance
-    }
-
-    fn new(board: &Board) -> Self {
-        let state: Vec<u8> = board
-            .get_state()
-            .into_iter()
-            .flat_map(|row| row.into_iter())
-            .collect();
-        let goal_state = board.get_goal_state();
-        State {
-            state,
-            goal_state,
-            blank_pos: 0, // Will be set in initialization
-            size: board.get_size(),
-            path: Vec::new(),
-        }
+                distance
+            })
+            .min()
+            .unwrap_or(0)
     }
 
     fn is_goal(&self) -> bool {
-        self.state == self.goal_state
+        self.goal_states
+            .iter()
+            .any(|goal| matches_goal(&self.state, goal))
     }
 
     fn get_possible_moves(&self) -> Vec<Direction> {
@@ -84,6 +79,9 @@ impl State {
             Direction::Right if col < self.size - 1 => self.blank_pos + 1,
             _ => return None,
         };
+        if self.blocked[new_pos] {
+            return None;
+        }
 
         let mut new_state = self.state.clone();
         new_state.swap(self.blank_pos, new_pos);
@@ -93,7 +91,8 @@ impl State {
 
         Some(State {
             state: new_state,
-            goal_state: self.goal_state.clone(),
+            goal_states: self.goal_states.clone(),
+            blocked: self.blocked.clone(),
             blank_pos: new_pos,
             size: self.size,
             path: new_path,
@@ -103,17 +102,24 @@ impl State {
 
 pub struct BFSSolver {
     initial_board: Board,
+    trace: bool,
 }
 
 impl BFSSolver {
-    fn find_blank_pos(state: &[u8]) -> usize {
+    /// Whether to print every level of the search as it is explored.
+    pub fn with_trace(mut self, trace: bool) -> Self {
+        self.trace = trace;
+        self
+    }
+
+    fn find_blank_pos(state: &[Tile]) -> usize {
         state.iter().position(|&x| x == 0).unwrap_or(0)
     }
 
     fn debug_print(
         current: &State,
         moves: &[(Direction, State, &str, String)],
-        visited: &HashSet<Vec<u8>>,
+        visited: &HashSet<Vec<Tile>>,
         level: usize,
     ) {
         println!("\nExploring Level {}", level);
@@ -159,7 +165,7 @@ impl BFSSolver {
     fn evaluate_move(
         current: &State,
         next: &State,
-        visited: &HashSet<Vec<u8>>,
+        visited: &HashSet<Vec<Tile>>,
     ) -> (&'static str, String) {
         let current_dist = current.manhattan_distance();
         let new_dist = next.manhattan_distance();
@@ -189,12 +195,14 @@ impl Solver for BFSSolver {
     fn new(initial: Board) -> Self {
         BFSSolver {
             initial_board: initial,
+            trace: true,
         }
     }
 
     fn new_with_goal(initial: Board) -> Self {
         BFSSolver {
             initial_board: initial,
+            trace: true,
         }
     }
 
@@ -203,17 +211,20 @@ impl Solver for BFSSolver {
         let mut visited = HashSet::new();
 
         // Initialize start state
-        let state: Vec<u8> = self
+        let state: Vec<Tile> = self
             .initial_board
             .get_state()
             .into_iter()
             .flat_map(|row| row.into_iter())
             .collect();
-        let goal_state = self.initial_board.get_goal_state();
+        let goal_states = self.initial_board.goal_states().to_vec();
 
         let initial_state = State {
             state: state.clone(),
-            goal_state,
+            goal_states,
+            blocked: (0..state.len())
+                .map(|pos| self.initial_board.is_blocked(pos))
+                .collect(),
             blank_pos: Self::find_blank_pos(&state),
             size: self.initial_board.get_size(),
             path: Vec::new(),
@@ -225,7 +236,7 @@ impl Solver for BFSSolver {
                 .get_state()
                 .into_iter()
                 .flatten()
-                .collect::<Vec<u8>>(),
+                .collect::<Vec<Tile>>(),
         );
 
         let max_depth = 30; // Increased max depth for BFS
@@ -236,7 +247,7 @@ impl Solver for BFSSolver {
             nodes_visited += 1;
 
             // Show level transition
-            if level > current_level {
+            if self.trace && level > current_level {
                 println!("\n{}", "Moving to next level...".blue());
                 current_level = level;
             }
@@ -244,7 +255,7 @@ impl Solver for BFSSolver {
             // Evaluate and print all possible moves
             let mut possible_moves = Vec::new();
             for direction in current_state.get_possible_moves() {
-                if let Some(new_state) = current_state.make_move(direction.clone()) {
+                if let Some(new_state) = current_state.make_move(direction) {
                     let (quality, reason) =
                         Self::evaluate_move(&current_state, &new_state, &visited);
                     possible_moves.push((direction, new_state, quality, reason));
@@ -253,19 +264,31 @@ impl Solver for BFSSolver {
 
             let display_moves: Vec<(Direction, State, &str, String)> = possible_moves
                 .iter()
-                .map(|(d, s, q, r)| (d.clone(), s.clone(), *q, r.clone()))
+                .map(|(d, s, q, r)| (*d, s.clone(), *q, r.clone()))
                 .collect();
 
-            Self::debug_print(&current_state, &display_moves, &visited, level);
+            if self.trace {
+                Self::debug_print(&current_state, &display_moves, &visited, level);
+            }
 
             if current_state.is_goal() {
-                println!("\n{}", "🎉 GOAL STATE REACHED! 🎉".green());
-                println!("BFS: Visited {} nodes", nodes_visited);
-                return Some(SolutionInfo::new(current_state.path, optimal_length));
+                if self.trace {
+                    println!("\n{}", "🎉 GOAL STATE REACHED! 🎉".green());
+                    println!("BFS: Visited {} nodes", nodes_visited);
+                }
+                let cost = self.initial_board.path_cost(&current_state.path);
+                let goal = self.initial_board.goal_reached_by(&current_state.path);
+                return Some(
+                    SolutionInfo::new(current_state.path, optimal_length)
+                        .with_cost(cost)
+                        .with_goal(goal),
+                );
             }
 
             if level >= max_depth {
-                println!("\n{}", "Max depth reached at this branch...".yellow());
+                if self.trace {
+                    println!("\n{}", "Max depth reached at this branch...".yellow());
+                }
                 continue;
             }
 
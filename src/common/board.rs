@@ -1,6 +1,26 @@
+use std::collections::{HashSet, VecDeque};
 use std::fmt;
 
-#[derive(Clone, Debug, PartialEq, Eq, Copy)]
+use super::{Heuristic, MoveCost};
+
+/// Tile number; 0 is the blank
+pub type Tile = u16;
+
+/// Goal entry for a cell whose final tile does not matter
+pub const WILDCARD: Tile = Tile::MAX;
+
+// States a solvability proof by search may visit before giving up on it
+const SOLVABILITY_SEARCH_LIMIT: usize = 500_000;
+
+/// Whether `state` satisfies `goal`, skipping wildcard cells.
+pub fn matches_goal(state: &[Tile], goal: &[Tile]) -> bool {
+    state
+        .iter()
+        .zip(goal)
+        .all(|(&tile, &wanted)| wanted == WILDCARD || tile == wanted)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Copy)]
 pub enum Direction {
     Up,
     Down,
@@ -8,12 +28,47 @@ pub enum Direction {
     Right,
 }
 
+impl Direction {
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+
+    /// One-letter form used in move strings such as "ULDR".
+    pub fn letter(&self) -> char {
+        match self {
+            Direction::Up => 'U',
+            Direction::Down => 'D',
+            Direction::Left => 'L',
+            Direction::Right => 'R',
+        }
+    }
+
+    pub fn from_letter(letter: char) -> Option<Direction> {
+        match letter.to_ascii_uppercase() {
+            'U' => Some(Direction::Up),
+            'D' => Some(Direction::Down),
+            'L' => Some(Direction::Left),
+            'R' => Some(Direction::Right),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Board {
-    state: Vec<u8>,
-    goal_state: Vec<u8>,
+    state: Vec<Tile>,
+    // Acceptable final configurations, never empty; the first is the primary goal
+    goal_states: Vec<Vec<Tile>>,
+    // Walls and glued tiles: cells the blank can never enter
+    blocked: Vec<bool>,
     blank_pos: usize,
     size: usize,
+    move_cost: MoveCost,
 }
 
 impl Board {
@@ -23,9 +78,9 @@ impl Board {
         let mut blank_pos = 0;
 
         // Convert 2D state to 1D and find blank position
-        for i in 0..size {
-            for j in 0..size {
-                let value = initial_state[i][j] as u8;
+        for (i, row) in initial_state.iter().enumerate() {
+            for (j, &cell) in row.iter().enumerate() {
+                let value = cell as Tile;
                 if value == 0 {
                     blank_pos = i * size + j;
                 }
@@ -34,44 +89,100 @@ impl Board {
         }
 
         // Default goal state: 1,2,3,4,5,6,7,8,0
-        let mut goal_state = (1..=(size * size) as u8).collect::<Vec<u8>>();
+        let mut goal_state = (1..=(size * size) as Tile).collect::<Vec<Tile>>();
         goal_state[(size * size) - 1] = 0;
 
         Board {
             state,
-            goal_state,
+            goal_states: vec![goal_state],
+            blocked: vec![false; size * size],
             blank_pos,
             size,
+            move_cost: MoveCost::Unit,
         }
     }
 
     pub fn new_with_goal(initial_state: Vec<Vec<i32>>, goal_state: Vec<Vec<i32>>) -> Self {
+        Self::new_with_goals(initial_state, vec![goal_state])
+    }
+
+    /// Board that is solved once it matches any of `goal_states`.
+    pub fn new_with_goals(initial_state: Vec<Vec<i32>>, goal_states: Vec<Vec<Vec<i32>>>) -> Self {
+        assert!(!goal_states.is_empty(), "a board needs at least one goal");
         let size = initial_state.len();
         let mut state = Vec::with_capacity(size * size);
-        let mut goal = Vec::with_capacity(size * size);
         let mut blank_pos = 0;
 
         // Convert 2D states to 1D
-        for i in 0..size {
-            for j in 0..size {
-                let value = initial_state[i][j] as u8;
+        for (i, row) in initial_state.iter().enumerate() {
+            for (j, &cell) in row.iter().enumerate() {
+                let value = cell as Tile;
                 if value == 0 {
                     blank_pos = i * size + j;
                 }
                 state.push(value);
-                goal.push(goal_state[i][j] as u8);
             }
         }
 
+        // Negative goal entries are wildcards
+        let goal_states = goal_states
+            .into_iter()
+            .map(|goal| {
+                goal.into_iter()
+                    .flatten()
+                    .map(|cell| if cell < 0 { WILDCARD } else { cell as Tile })
+                    .collect()
+            })
+            .collect();
+
         Board {
             state,
-            goal_state: goal,
+            goal_states,
+            blocked: vec![false; size * size],
             blank_pos,
             size,
+            move_cost: MoveCost::Unit,
         }
     }
 
-    pub fn get_state(&self) -> Vec<Vec<u8>> {
+    /// Goal with the numbers in reading order and the blank last.
+    pub fn standard_goal(size: usize) -> Vec<Vec<i32>> {
+        (0..size)
+            .map(|row| {
+                (0..size)
+                    .map(|col| ((row * size + col + 1) % (size * size)) as i32)
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Goal with the numbers spiralling clockwise in from the top-left corner,
+    /// ending with the blank.
+    pub fn snail_goal(size: usize) -> Vec<Vec<i32>> {
+        const HEADINGS: [(isize, isize); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+        let mut grid = vec![vec![-1; size]; size];
+        let (mut row, mut col, mut heading) = (0, 0, 0);
+
+        for value in 1..(size * size) as i32 {
+            grid[row][col] = value;
+            // Turn right at the border or at an already numbered cell
+            let ahead = |heading: usize| {
+                let (dr, dc) = HEADINGS[heading];
+                let (r, c) = (row as isize + dr, col as isize + dc);
+                (r >= 0 && c >= 0 && (r as usize) < size && (c as usize) < size)
+                    .then_some((r as usize, c as usize))
+                    .filter(|&(r, c)| grid[r][c] == -1)
+            };
+            if ahead(heading).is_none() {
+                heading = (heading + 1) % 4;
+            }
+            (row, col) = ahead(heading).expect("spiral continues");
+        }
+        grid[row][col] = 0;
+        grid
+    }
+
+    pub fn get_state(&self) -> Vec<Vec<Tile>> {
         let mut result = Vec::with_capacity(self.size);
         for i in 0..self.size {
             let mut row = Vec::with_capacity(self.size);
@@ -83,54 +194,334 @@ impl Board {
         result
     }
 
-    pub fn get_goal_state(&self) -> Vec<u8> {
-        self.goal_state.clone()
+    pub fn get_goal_state(&self) -> Vec<Tile> {
+        self.goal_states[0].clone()
+    }
+
+    pub fn goal_states(&self) -> &[Vec<Tile>] {
+        &self.goal_states
     }
 
     pub fn get_size(&self) -> usize {
         self.size
     }
 
-    pub fn is_goal(&self) -> bool {
-        self.state == self.goal_state
+    pub fn with_move_cost(mut self, move_cost: MoveCost) -> Self {
+        self.move_cost = move_cost;
+        self
     }
 
-    pub fn get_possible_moves(&self) -> Vec<Direction> {
-        let mut moves = Vec::new();
+    /// Marks cells whose tile never moves; `true` entries are blocked.
+    pub fn with_blocked_cells(mut self, mask: Vec<Vec<bool>>) -> Self {
+        self.blocked = mask.into_iter().flatten().collect();
+        assert_eq!(
+            self.blocked.len(),
+            self.state.len(),
+            "mask must cover the board"
+        );
+        assert!(!self.blocked[self.blank_pos], "the blank cannot be blocked");
+        self
+    }
+
+    pub fn is_blocked(&self, pos: usize) -> bool {
+        self.blocked[pos]
+    }
+
+    pub fn has_blocked_cells(&self) -> bool {
+        self.blocked.contains(&true)
+    }
+
+    // Cell the blank moves to in `dir`, if it is on the board and not blocked
+    fn target_of(&self, dir: Direction) -> Option<usize> {
         let row = self.blank_pos / self.size;
         let col = self.blank_pos % self.size;
 
-        if row > 0 {
-            moves.push(Direction::Up);
+        let target = match dir {
+            Direction::Up if row > 0 => self.blank_pos - self.size,
+            Direction::Down if row < self.size - 1 => self.blank_pos + self.size,
+            Direction::Left if col > 0 => self.blank_pos - 1,
+            Direction::Right if col < self.size - 1 => self.blank_pos + 1,
+            _ => return None,
+        };
+        (!self.blocked[target]).then_some(target)
+    }
+
+    pub fn get_move_cost(&self) -> &MoveCost {
+        &self.move_cost
+    }
+
+    // Cost of the tile that slides into the blank when moving in `dir`
+    pub fn cost_of_move(&self, dir: Direction) -> Option<u32> {
+        let tile_pos = self.target_of(dir)?;
+        Some(self.move_cost.of(self.state[tile_pos]))
+    }
+
+    // Total cost of playing `moves` from this board, up to the first invalid move
+    pub fn path_cost(&self, moves: &[Direction]) -> u32 {
+        let mut board = self.clone();
+        let mut cost = 0;
+        for &dir in moves {
+            match board.cost_of_move(dir) {
+                Some(move_cost) => {
+                    cost += move_cost;
+                    board.make_move(dir).unwrap();
+                }
+                None => break,
+            }
         }
-        if row < self.size - 1 {
-            moves.push(Direction::Down);
+        cost
+    }
+
+    pub fn tiles(&self) -> &[Tile] {
+        &self.state
+    }
+
+    pub fn blank_pos(&self) -> usize {
+        self.blank_pos
+    }
+
+    // With several goals only the nearest one bounds the remaining cost
+    pub fn heuristic(&self, heuristic: Heuristic) -> u32 {
+        self.goal_states
+            .iter()
+            .map(|goal| heuristic.evaluate(&self.state, goal, self.size, &self.move_cost))
+            .min()
+            .unwrap_or(0)
+    }
+
+    pub fn is_solvable(&self) -> bool {
+        self.reachable_goal().is_some()
+    }
+
+    /// The first goal that can be reached from this board, with its wildcards
+    /// filled in by the leftover tiles. `None` if no goal is reachable.
+    ///
+    /// Parity settles this whenever the free cells form a 2-connected region
+    /// other than a plain cycle. Blocked cells can break that, and then the
+    /// state space is searched instead, as far as a fixed budget allows.
+    pub fn reachable_goal(&self) -> Option<Vec<Tile>> {
+        if !self.parity_decides() {
+            if let Some(proof) = self.search_for_goal() {
+                return proof;
+            }
         }
-        if col > 0 {
-            moves.push(Direction::Left);
+        self.goal_states
+            .iter()
+            .find_map(|goal| self.reachable_completion(goal))
+    }
+
+    // Fills the wildcards of `goal` with the leftover tiles in an arrangement
+    // of the right parity
+    fn reachable_completion(&self, goal: &[Tile]) -> Option<Vec<Tile>> {
+        let mut goal = goal.to_vec();
+        // Blocked tiles stay where they are
+        for pos in (0..goal.len()).filter(|&pos| self.blocked[pos]) {
+            match goal[pos] {
+                WILDCARD => goal[pos] = self.state[pos],
+                wanted if wanted != self.state[pos] => return None,
+                _ => {}
+            }
+        }
+        let pattern = goal.clone();
+        let free: Vec<usize> = (0..goal.len())
+            .filter(|&pos| goal[pos] == WILDCARD)
+            .collect();
+        let mut leftover: Vec<Tile> = self
+            .state
+            .iter()
+            .copied()
+            .filter(|tile| !pattern.contains(tile))
+            .collect();
+        leftover.sort_unstable();
+        for (&pos, &tile) in free.iter().zip(&leftover) {
+            goal[pos] = tile;
+        }
+        if self.can_reach(&goal) {
+            return Some(goal);
+        }
+
+        // Exchanging two filled-in tiles flips the permutation parity
+        for (i, &first) in free.iter().enumerate() {
+            for &second in &free[i + 1..] {
+                goal.swap(first, second);
+                if self.can_reach(&goal) {
+                    return Some(goal);
+                }
+                goal.swap(first, second);
+            }
+        }
+        None
+    }
+
+    // Parity is exact on a 2-connected bipartite region that is not a cycle
+    fn parity_decides(&self) -> bool {
+        if !self.has_blocked_cells() {
+            return true;
+        }
+        let free: Vec<usize> = (0..self.state.len())
+            .filter(|&pos| !self.blocked[pos])
+            .collect();
+        let edges = free
+            .iter()
+            .map(|&pos| self.free_neighbours(pos).count())
+            .sum::<usize>()
+            / 2;
+        if free.len() < 3 || edges == free.len() {
+            return false;
+        }
+
+        let mut discovered = vec![usize::MAX; self.state.len()];
+        let mut low = vec![0; self.state.len()];
+        let mut timer = 0;
+        let root = free[0];
+        let cut_vertex = self.find_cut_vertex(root, None, &mut discovered, &mut low, &mut timer);
+        !cut_vertex && timer == free.len()
+    }
+
+    fn free_neighbours(&self, pos: usize) -> impl Iterator<Item = usize> + '_ {
+        let (row, col) = (pos / self.size, pos % self.size);
+        [
+            (row > 0).then(|| pos - self.size),
+            (row + 1 < self.size).then(|| pos + self.size),
+            (col > 0).then(|| pos - 1),
+            (col + 1 < self.size).then(|| pos + 1),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|&next| !self.blocked[next])
+    }
+
+    // Tarjan's depth-first search for articulation points among the free cells
+    fn find_cut_vertex(
+        &self,
+        pos: usize,
+        parent: Option<usize>,
+        discovered: &mut [usize],
+        low: &mut [usize],
+        timer: &mut usize,
+    ) -> bool {
+        discovered[pos] = *timer;
+        low[pos] = *timer;
+        *timer += 1;
+        let mut children = 0;
+
+        for next in self.free_neighbours(pos) {
+            if discovered[next] == usize::MAX {
+                children += 1;
+                if self.find_cut_vertex(next, Some(pos), discovered, low, timer) {
+                    return true;
+                }
+                low[pos] = low[pos].min(low[next]);
+                if parent.is_some() && low[next] >= discovered[pos] {
+                    return true;
+                }
+            } else if Some(next) != parent {
+                low[pos] = low[pos].min(discovered[next]);
+            }
+        }
+        parent.is_none() && children > 1
+    }
+
+    // Breadth-first proof: `Some(goal)` or `Some(None)` once settled, `None`
+    // if the search budget ran out first
+    fn search_for_goal(&self) -> Option<Option<Vec<Tile>>> {
+        let mut seen = HashSet::from([self.state.clone()]);
+        let mut queue = VecDeque::from([self.clone()]);
+
+        while let Some(board) = queue.pop_front() {
+            if board.is_goal() {
+                return Some(Some(board.state));
+            }
+            for dir in board.get_possible_moves() {
+                let mut next = board.clone();
+                next.make_move(dir).unwrap();
+                if seen.insert(next.state.clone()) {
+                    if seen.len() > SOLVABILITY_SEARCH_LIMIT {
+                        return None;
+                    }
+                    queue.push_back(next);
+                }
+            }
         }
-        if col < self.size - 1 {
-            moves.push(Direction::Right);
+        Some(None)
+    }
+
+    // Every move swaps the blank with a neighbour: it flips the parity of the
+    // tile permutation and of the blank's distance to its goal cell together.
+    fn can_reach(&self, goal: &[Tile]) -> bool {
+        let mut goal_pos = vec![usize::MAX; goal.len()];
+        for (pos, &value) in goal.iter().enumerate() {
+            match goal_pos.get_mut(value as usize) {
+                Some(slot) => *slot = pos,
+                None => return false,
+            }
+        }
+
+        let mut seen = vec![false; self.state.len()];
+        let mut transpositions = 0;
+        for start in 0..self.state.len() {
+            let mut pos = start;
+            let mut cycle_len = 0;
+            while !seen[pos] {
+                seen[pos] = true;
+                pos = match goal_pos.get(self.state[pos] as usize) {
+                    Some(&target) if target != usize::MAX => target,
+                    _ => return false,
+                };
+                cycle_len += 1;
+            }
+            if cycle_len > 0 {
+                transpositions += cycle_len - 1;
+            }
+        }
+
+        let blank_goal = goal_pos[0];
+        let blank_distance = (self.blank_pos / self.size).abs_diff(blank_goal / self.size)
+            + (self.blank_pos % self.size).abs_diff(blank_goal % self.size);
+
+        transpositions % 2 == blank_distance % 2
+    }
+
+    pub fn is_goal(&self) -> bool {
+        self.reached_goal().is_some()
+    }
+
+    /// Index of the goal this board currently matches.
+    pub fn reached_goal(&self) -> Option<usize> {
+        self.goal_states
+            .iter()
+            .position(|goal| matches_goal(&self.state, goal))
+    }
+
+    /// Index of the goal reached by playing `moves` from this board.
+    pub fn goal_reached_by(&self, moves: &[Direction]) -> Option<usize> {
+        let mut board = self.clone();
+        for &dir in moves {
+            board.make_move(dir).ok()?;
         }
+        board.reached_goal()
+    }
 
-        moves
+    pub fn get_possible_moves(&self) -> Vec<Direction> {
+        [
+            Direction::Up,
+            Direction::Down,
+            Direction::Left,
+            Direction::Right,
+        ]
+        .into_iter()
+        .filter(|&dir| self.target_of(dir).is_some())
+        .collect()
     }
-    pub fn get_row(&self, row: usize) -> Vec<u8> {
+    pub fn get_row(&self, row: usize) -> Vec<Tile> {
         let start = row * self.size;
         let end = start + self.size;
         self.state[start..end].to_vec()
     }
     pub fn make_move(&mut self, dir: Direction) -> Result<(), String> {
-        let row = self.blank_pos / self.size;
-        let col = self.blank_pos % self.size;
-
-        let new_pos = match dir {
-            Direction::Up if row > 0 => self.blank_pos - self.size,
-            Direction::Down if row < self.size - 1 => self.blank_pos + self.size,
-            Direction::Left if col > 0 => self.blank_pos - 1,
-            Direction::Right if col < self.size - 1 => self.blank_pos + 1,
-            _ => return Err("Invalid move".to_string()),
-        };
+        let new_pos = self
+            .target_of(dir)
+            .ok_or_else(|| "Invalid move".to_string())?;
 
         self.state.swap(self.blank_pos, new_pos);
         self.blank_pos = new_pos;
@@ -139,22 +530,29 @@ impl Board {
     }
 
     pub fn manhattan_distance(&self) -> u32 {
-        let mut distance = 0;
-        for pos in 0..self.state.len() {
-            let value = self.state[pos];
-            if value != 0 {
-                // Find position of this value in goal state
-                if let Some(goal_pos) = self.goal_state.iter().position(|&x| x == value) {
-                    let current_row = pos / self.size;
-                    let current_col = pos % self.size;
-                    let goal_row = goal_pos / self.size;
-                    let goal_col = goal_pos % self.size;
-                    distance +=
-                        (current_row.abs_diff(goal_row) + current_col.abs_diff(goal_col)) as u32;
+        self.goal_states
+            .iter()
+            .map(|goal_state| {
+                let mut distance = 0;
+                for pos in 0..self.state.len() {
+                    let value = self.state[pos];
+                    if value != 0 {
+                        // Find position of this value in goal state
+                        if let Some(goal_pos) = goal_state.iter().position(|&x| x == value) {
+                            let current_row = pos / self.size;
+                            let current_col = pos % self.size;
+                            let goal_row = goal_pos / self.size;
+                            let goal_col = goal_pos % self.size;
+                            distance += (current_row.abs_diff(goal_row)
+                                + current_col.abs_diff(goal_col))
+                                as u32;
+                        }
+                    }
                 }
-            }
-        }
-        distance
+                distance
+            })
+            .min()
+            .unwrap_or(0)
     }
 }
 
@@ -174,9 +572,12 @@ impl fmt::Display for Board {
         for row in 0..self.size {
             write!(f, "│")?;
             for col in 0..self.size {
-                let num = self.state[row * self.size + col];
+                let pos = row * self.size + col;
+                let num = self.state[pos];
                 if num == 0 {
                     write!(f, " _ │")?;
+                } else if self.blocked[pos] {
+                    write!(f, "[{}]│", num)?;
                 } else {
                     write!(f, " {} │", num)?;
                 }
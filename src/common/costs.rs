@@ -0,0 +1,53 @@
+use super::Tile;
+
+/// Price of sliding a tile, charged once per move.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum MoveCost {
+    /// Every move costs 1, so cost and move count coincide
+    #[default]
+    Unit,
+    /// Moving a tile costs its number
+    FaceValue,
+    /// Cost per tile number; tiles missing from the table cost 1. Build it
+    /// with `MoveCost::table`, which rejects free moves.
+    Table(Vec<u32>),
+}
+
+impl MoveCost {
+    /// A per-tile table. Every tile must cost at least 1, or the heuristics
+    /// that count moves would overestimate; entry 0, the blank, is ignored.
+    pub fn table(costs: Vec<u32>) -> Result<MoveCost, String> {
+        match costs.iter().skip(1).position(|&cost| cost == 0) {
+            Some(tile) => Err(format!("tile {} cannot cost 0", tile + 1)),
+            None => Ok(MoveCost::Table(costs)),
+        }
+    }
+
+    pub fn of(&self, tile: Tile) -> u32 {
+        match self {
+            MoveCost::Unit => 1,
+            MoveCost::FaceValue => tile as u32,
+            MoveCost::Table(costs) => costs.get(tile as usize).copied().unwrap_or(1),
+        }
+    }
+
+    pub fn is_unit(&self) -> bool {
+        *self == MoveCost::Unit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_rejects_free_tiles() {
+        assert_eq!(
+            MoveCost::table(vec![0, 3, 0, 2]),
+            Err("tile 2 cannot cost 0".to_string())
+        );
+        let costs = MoveCost::table(vec![0, 3, 1, 2]).unwrap();
+        assert_eq!(costs.of(1), 3);
+        assert_eq!(costs.of(7), 1);
+    }
+}
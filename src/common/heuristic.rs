@@ -0,0 +1,158 @@
+use super::{MoveCost, Tile, WILDCARD};
+
+/// Admissible estimates of the cost left to reach the goal. Only the weighted
+/// variant accounts for move costs; the others assume every move costs at least 1.
+/// Tiles without a place in the goal (wildcards) contribute nothing.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Heuristic {
+    MisplacedTiles,
+    #[default]
+    Manhattan,
+    LinearConflict,
+    /// Manhattan distance of each tile times the cost of moving it
+    WeightedManhattan,
+}
+
+impl Heuristic {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Heuristic::MisplacedTiles => "Misplaced tiles",
+            Heuristic::Manhattan => "Manhattan",
+            Heuristic::LinearConflict => "Linear conflict",
+            Heuristic::WeightedManhattan => "Weighted Manhattan",
+        }
+    }
+
+    pub fn evaluate(
+        &self,
+        state: &[Tile],
+        goal_state: &[Tile],
+        size: usize,
+        move_cost: &MoveCost,
+    ) -> u32 {
+        match self {
+            Heuristic::MisplacedTiles => misplaced_tiles(state, goal_state),
+            Heuristic::Manhattan => manhattan_distance(state, goal_state, size),
+            Heuristic::LinearConflict => {
+                manhattan_distance(state, goal_state, size)
+                    + linear_conflict(state, goal_state, size)
+            }
+            Heuristic::WeightedManhattan => {
+                weighted_manhattan_distance(state, goal_state, size, move_cost)
+            }
+        }
+    }
+}
+
+// Index of every tile value in the goal state, `None` for tiles it leaves open
+fn goal_positions(goal_state: &[Tile]) -> Vec<Option<usize>> {
+    let mut positions = vec![None; goal_state.len()];
+    for (pos, &value) in goal_state.iter().enumerate() {
+        if value != WILDCARD {
+            positions[value as usize] = Some(pos);
+        }
+    }
+    positions
+}
+
+fn misplaced_tiles(state: &[Tile], goal_state: &[Tile]) -> u32 {
+    state
+        .iter()
+        .zip(goal_state)
+        .filter(|(&value, &goal)| value != 0 && goal != WILDCARD && value != goal)
+        .count() as u32
+}
+
+fn manhattan_distance(state: &[Tile], goal_state: &[Tile], size: usize) -> u32 {
+    weighted_manhattan_distance(state, goal_state, size, &MoveCost::Unit)
+}
+
+// Every tile has to slide at least its Manhattan distance, paying its cost each time
+fn weighted_manhattan_distance(
+    state: &[Tile],
+    goal_state: &[Tile],
+    size: usize,
+    move_cost: &MoveCost,
+) -> u32 {
+    let goal_pos = goal_positions(goal_state);
+    let mut distance = 0;
+    for (pos, &value) in state.iter().enumerate().filter(|(_, &value)| value != 0) {
+        if let Some(target) = goal_pos[value as usize] {
+            let moves = (pos / size).abs_diff(target / size) + (pos % size).abs_diff(target % size);
+            distance += moves as u32 * move_cost.of(value);
+        }
+    }
+    distance
+}
+
+// Extra moves needed for tiles that sit in their goal row (or column) but in
+// reversed order. Every tile that has to leave its line to let the others
+// pass costs two moves; counting the fewest such tiles keeps it admissible.
+fn linear_conflict(state: &[Tile], goal_state: &[Tile], size: usize) -> u32 {
+    let goal_pos = goal_positions(goal_state);
+    // Goal cell of the tile at `pos`, if it is a real tile with a fixed place
+    let target = |pos: usize| match state[pos] {
+        0 => None,
+        value => goal_pos[value as usize],
+    };
+    let mut extra = 0;
+
+    for line in 0..size {
+        // (position along the line, goal position along the line) for rows, then columns
+        let row: Vec<(usize, usize)> = (0..size)
+            .map(|col| line * size + col)
+            .filter_map(|pos| target(pos).map(|goal| (pos, goal)))
+            .filter(|&(_, goal)| goal / size == line)
+            .map(|(pos, goal)| (pos % size, goal % size))
+            .collect();
+        let column: Vec<(usize, usize)> = (0..size)
+            .map(|row| row * size + line)
+            .filter_map(|pos| target(pos).map(|goal| (pos, goal)))
+            .filter(|&(_, goal)| goal % size == line)
+            .map(|(pos, goal)| (pos / size, goal / size))
+            .collect();
+
+        extra += 2 * (conflicts_to_remove(row) + conflicts_to_remove(column));
+    }
+    extra
+}
+
+// Fewest tiles to take out of a line so the rest are in goal order: those
+// outside a longest increasing run of goal positions. `tiles` is sorted by
+// position along the line.
+fn conflicts_to_remove(tiles: Vec<(usize, usize)>) -> u32 {
+    // Smallest last goal position of an increasing run of each length
+    let mut tails: Vec<usize> = Vec::new();
+    for &(_, goal) in &tiles {
+        match tails.binary_search(&goal) {
+            Ok(_) => {}
+            Err(length) if length == tails.len() => tails.push(goal),
+            Err(length) => tails[length] = goal,
+        }
+    }
+    (tiles.len() - tails.len()) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Board;
+
+    fn with_first_row(size: usize, row: &[i32]) -> Board {
+        let mut grid = Board::standard_goal(size);
+        grid[0] = row.to_vec();
+        Board::new(grid)
+    }
+
+    #[test]
+    fn test_linear_conflict_removes_fewest_tiles() {
+        // 1, 2 and 4 are already in order, so only 3 and 5 need to step aside
+        let board = with_first_row(5, &[3, 1, 5, 2, 4]);
+        assert_eq!(board.heuristic(Heuristic::Manhattan), 8);
+        assert_eq!(board.heuristic(Heuristic::LinearConflict), 8 + 2 * 2);
+
+        let reversed = with_first_row(4, &[4, 3, 2, 1]);
+        assert_eq!(reversed.heuristic(Heuristic::Manhattan), 8);
+        assert_eq!(reversed.heuristic(Heuristic::LinearConflict), 8 + 2 * 3);
+    }
+}
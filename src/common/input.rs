@@ -0,0 +1,327 @@
+use serde_json::{json, Value};
+use std::fs;
+
+use super::{Board, Direction, MoveCost};
+use crate::SolutionInfo;
+
+/// Reads and parses a JSON file.
+pub fn read_json(path: &str) -> Result<Value, String> {
+    let content =
+        fs::read_to_string(path).map_err(|err| format!("cannot read {}: {}", path, err))?;
+    serde_json::from_str(&content).map_err(|err| format!("invalid JSON in {}: {}", path, err))
+}
+
+// Rows of a grid; "*" or null marks a wildcard cell (goal only)
+fn parse_grid(grid: &Value) -> Result<Vec<Vec<i32>>, String> {
+    let rows = grid.as_array().ok_or("board must be an array of rows")?;
+    let grid = rows
+        .iter()
+        .map(|row| {
+            row.as_array()
+                .ok_or("each row must be an array")?
+                .iter()
+                .map(|x| match x {
+                    Value::Null => Ok(-1),
+                    Value::String(s) if s == "*" => Ok(-1),
+                    _ => x
+                        .as_i64()
+                        .map(|n| n as i32)
+                        .ok_or_else(|| format!("invalid cell {}", x)),
+                })
+                .collect()
+        })
+        .collect::<Result<Vec<Vec<i32>>, String>>()?;
+
+    if grid.is_empty() || grid.iter().any(|row| row.len() != grid.len()) {
+        return Err("board must be square".to_string());
+    }
+    Ok(grid)
+}
+
+// Every number in 0..size² exactly once, except for wildcards when allowed
+fn check_tiles(grid: &[Vec<i32>], wildcards: bool) -> Result<(), String> {
+    let cells = grid.len() * grid.len();
+    let mut seen = vec![false; cells];
+    for &cell in grid.iter().flatten() {
+        if cell < 0 && wildcards {
+            continue;
+        }
+        if cell < 0 || cell as usize >= cells || seen[cell as usize] {
+            return Err(format!("tile {} is out of range or repeated", cell));
+        }
+        seen[cell as usize] = true;
+    }
+    if !wildcards && seen.contains(&false) {
+        return Err("board is missing tiles".to_string());
+    }
+    Ok(())
+}
+
+fn parse_board(json: &Value) -> Result<Vec<Vec<i32>>, String> {
+    parse_grid(json.get("board").ok_or("missing board field")?)
+}
+
+// Either a "goals" list of grids (or "snail"), or a single "board"
+fn parse_goals(json: &Value, size: usize) -> Result<Vec<Vec<Vec<i32>>>, String> {
+    let goals = match json.get("goals") {
+        Some(Value::Array(goals)) => goals
+            .iter()
+            .map(|goal| match goal {
+                Value::String(name) if name == "snail" => Ok(Board::snail_goal(size)),
+                _ => parse_grid(goal),
+            })
+            .collect::<Result<Vec<_>, String>>()?,
+        Some(_) => return Err("goals must be an array".to_string()),
+        None => vec![parse_board(json)?],
+    };
+
+    if goals.is_empty() {
+        return Err("goals must not be empty".to_string());
+    }
+    for goal in &goals {
+        if goal.len() != size {
+            return Err("goal size does not match the board".to_string());
+        }
+        check_tiles(goal, true)?;
+    }
+    Ok(goals)
+}
+
+// Blocked-cell mask with the board's shape; true or 1 marks a blocked cell
+fn parse_mask(mask: &Value, size: usize) -> Result<Vec<Vec<bool>>, String> {
+    let mask = mask
+        .as_array()
+        .ok_or("blocked must be an array of rows")?
+        .iter()
+        .map(|row| {
+            row.as_array()
+                .ok_or("each blocked row must be an array")?
+                .iter()
+                .map(|x| match x {
+                    Value::Bool(blocked) => Ok(*blocked),
+                    _ => x
+                        .as_u64()
+                        .map(|flag| flag != 0)
+                        .ok_or_else(|| format!("invalid blocked flag {}", x)),
+                })
+                .collect()
+        })
+        .collect::<Result<Vec<Vec<bool>>, String>>()?;
+
+    if mask.len() != size || mask.iter().any(|row| row.len() != size) {
+        return Err("blocked mask size does not match the board".to_string());
+    }
+    Ok(mask)
+}
+
+fn parse_move_cost(json: &Value) -> Result<MoveCost, String> {
+    match json.get("move_costs") {
+        None => Ok(MoveCost::Unit),
+        Some(Value::String(kind)) if kind == "face_value" => Ok(MoveCost::FaceValue),
+        Some(Value::Array(costs)) => costs
+            .iter()
+            .map(|x| {
+                x.as_u64()
+                    .map(|cost| cost as u32)
+                    .ok_or_else(|| format!("invalid move cost {}", x))
+            })
+            .collect::<Result<Vec<u32>, String>>()
+            .and_then(MoveCost::table),
+        Some(_) => Err("invalid move_costs format".to_string()),
+    }
+}
+
+/// Builds a board from a puzzle object ("board", optional "move_costs" and
+/// "blocked") and a goal object ("goals" or "board").
+pub fn board_from_json(puzzle: &Value, goal: &Value) -> Result<Board, String> {
+    let initial_state = parse_board(puzzle)?;
+    check_tiles(&initial_state, false)?;
+    let size = initial_state.len();
+    let goals = parse_goals(goal, size)?;
+    let move_cost = parse_move_cost(puzzle)?;
+
+    let board = Board::new_with_goals(initial_state, goals).with_move_cost(move_cost);
+    match puzzle.get("blocked") {
+        None => Ok(board),
+        Some(mask) => {
+            let mask = parse_mask(mask, size)?;
+            if mask[board.blank_pos() / size][board.blank_pos() % size] {
+                return Err("the blank cannot be blocked".to_string());
+            }
+            Ok(board.with_blocked_cells(mask))
+        }
+    }
+}
+
+/// Loads the puzzle at `initial_path` with the goal described at `goal_path`.
+pub fn load_board(initial_path: &str, goal_path: &str) -> Result<Board, String> {
+    board_from_json(&read_json(initial_path)?, &read_json(goal_path)?)
+}
+
+/// The board in the format `board_from_json` reads.
+pub fn board_to_json(board: &Board) -> Value {
+    json!({
+        "size": board.get_size(),
+        "board": board.get_state(),
+    })
+}
+
+/// Parses a move string such as "ULDR"; spaces and commas are ignored.
+pub fn parse_moves(text: &str) -> Result<Vec<Direction>, String> {
+    text.chars()
+        .filter(|c| !c.is_whitespace() && *c != ',')
+        .map(|c| Direction::from_letter(c).ok_or_else(|| format!("invalid move '{}'", c)))
+        .collect()
+}
+
+pub fn format_moves(moves: &[Direction]) -> String {
+    moves.iter().map(Direction::letter).collect()
+}
+
+// "ULDR", or a list of letters or direction names such as ["Up", "L"]
+fn moves_from_json(moves: &Value) -> Result<Vec<Direction>, String> {
+    match moves {
+        Value::String(text) => parse_moves(text),
+        Value::Array(items) => items
+            .iter()
+            .map(|item| {
+                let name = item
+                    .as_str()
+                    .ok_or_else(|| format!("invalid move {}", item))?;
+                let direction = match name.to_ascii_lowercase().as_str() {
+                    "up" => Some(Direction::Up),
+                    "down" => Some(Direction::Down),
+                    "left" => Some(Direction::Left),
+                    "right" => Some(Direction::Right),
+                    _ if name.chars().count() == 1 => {
+                        name.chars().next().and_then(Direction::from_letter)
+                    }
+                    _ => None,
+                };
+                direction.ok_or_else(|| format!("invalid move '{}'", name))
+            })
+            .collect(),
+        _ => Err("moves must be a string or an array".to_string()),
+    }
+}
+
+/// One move sequence of a solution file.
+pub struct SolutionEntry {
+    /// The solver that found it, when the file says
+    pub solver: Option<String>,
+    pub moves: Vec<Direction>,
+}
+
+/// Reads the move sequences of a solution file: plain move letters, a JSON
+/// object with "moves", or the output of `solve --format json` with one
+/// sequence per solver.
+pub fn read_solutions(path: &str) -> Result<Vec<SolutionEntry>, String> {
+    let content =
+        fs::read_to_string(path).map_err(|err| format!("cannot read {}: {}", path, err))?;
+    let Ok(json) = serde_json::from_str::<Value>(&content) else {
+        return Ok(vec![SolutionEntry {
+            solver: None,
+            moves: parse_moves(&content)?,
+        }]);
+    };
+
+    let entry = |object: &Value| {
+        Ok(SolutionEntry {
+            solver: object
+                .get("solver")
+                .and_then(Value::as_str)
+                .map(String::from),
+            moves: moves_from_json(&object["moves"])?,
+        })
+    };
+    match (json.get("moves"), json.get("results")) {
+        (Some(_), _) => Ok(vec![entry(&json)?]),
+        (None, Some(Value::Array(results))) => results
+            .iter()
+            // Solvers that failed have no moves to check
+            .filter(|result| result.get("moves").is_some())
+            .map(entry)
+            .collect(),
+        _ => Err(format!("{} has no moves or results field", path)),
+    }
+}
+
+/// The moves, length, cost and search counters of a solution.
+pub fn solution_to_json(solution: &SolutionInfo) -> Value {
+    json!({
+        "moves": format_moves(&solution.moves),
+        "length": solution.moves.len(),
+        "cost": solution.total_cost,
+        "nodes_expanded": solution.stats.nodes_expanded,
+        "nodes_generated": solution.stats.nodes_generated,
+        "max_frontier": solution.stats.max_frontier,
+    })
+}
+
+/// One puzzle of a batch file, or why it could not be read.
+pub struct PuzzleEntry {
+    /// The puzzle's "id" field, echoed back in its result
+    pub id: Option<Value>,
+    pub board: Result<Board, String>,
+}
+
+// A puzzle object carries its own "goal" or "goals", or takes the shared one
+fn puzzle_entry(puzzle: &Value, shared_goal: Option<&Value>) -> PuzzleEntry {
+    let board = if puzzle.get("goals").is_some() {
+        board_from_json(puzzle, puzzle)
+    } else if let Some(goal) = puzzle.get("goal") {
+        board_from_json(puzzle, &json!({ "goals": [goal] }))
+    } else if let Some(goal) = shared_goal {
+        board_from_json(puzzle, goal)
+    } else {
+        let size = puzzle["board"].as_array().map_or(0, Vec::len);
+        board_from_json(puzzle, &json!({ "board": Board::standard_goal(size) }))
+    };
+    PuzzleEntry {
+        id: puzzle.get("id").cloned(),
+        board,
+    }
+}
+
+/// Reads a batch of puzzles: a JSON array of puzzle objects, an object with a
+/// "puzzles" array (and optionally shared "goals"), or JSON Lines with one
+/// puzzle per line. Puzzles without a goal of their own use `default_goal`,
+/// then the standard goal. Malformed puzzles become entries with an error
+/// rather than failing the whole batch.
+pub fn read_puzzles(path: &str, default_goal: Option<&Value>) -> Result<Vec<PuzzleEntry>, String> {
+    let content =
+        fs::read_to_string(path).map_err(|err| format!("cannot read {}: {}", path, err))?;
+
+    match serde_json::from_str::<Value>(&content) {
+        Ok(Value::Array(puzzles)) => Ok(puzzles
+            .iter()
+            .map(|puzzle| puzzle_entry(puzzle, default_goal))
+            .collect()),
+        Ok(Value::Object(file)) if file.contains_key("puzzles") => {
+            let puzzles = file["puzzles"]
+                .as_array()
+                .ok_or("puzzles must be an array")?;
+            let shared_goal = Value::Object(file.clone());
+            let shared_goal = if file.contains_key("goals") {
+                Some(&shared_goal)
+            } else {
+                default_goal
+            };
+            Ok(puzzles
+                .iter()
+                .map(|puzzle| puzzle_entry(puzzle, shared_goal))
+                .collect())
+        }
+        _ => Ok(content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| match serde_json::from_str::<Value>(line) {
+                Ok(puzzle) => puzzle_entry(&puzzle, default_goal),
+                Err(err) => PuzzleEntry {
+                    id: None,
+                    board: Err(format!("invalid JSON: {}", err)),
+                },
+            })
+            .collect()),
+    }
+}
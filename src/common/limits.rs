@@ -0,0 +1,27 @@
+use std::time::{Duration, Instant};
+
+/// Budgets after which a search gives up instead of running to completion.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SearchLimits {
+    pub max_nodes: Option<usize>,
+    pub time_limit: Option<Duration>,
+}
+
+impl SearchLimits {
+    pub fn with_max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = Some(max_nodes);
+        self
+    }
+
+    pub fn with_time_limit(mut self, time_limit: Duration) -> Self {
+        self.time_limit = Some(time_limit);
+        self
+    }
+
+    pub fn exceeded(&self, nodes_expanded: usize, started: Instant) -> bool {
+        self.max_nodes.is_some_and(|max| nodes_expanded >= max)
+            || self
+                .time_limit
+                .is_some_and(|limit| started.elapsed() >= limit)
+    }
+}
@@ -1,7 +1,22 @@
 pub mod board;
+pub mod costs;
+pub mod heuristic;
+pub mod input;
+pub mod limits;
+pub mod pattern;
+pub mod pruning;
+pub mod stats;
 pub mod utils;
+pub mod verify;
 pub mod visualization;
 
-pub use board::{Board, Direction};
+pub use board::{matches_goal, Board, Direction, Tile, WILDCARD};
+pub use costs::MoveCost;
+pub use heuristic::Heuristic;
+pub use limits::SearchLimits;
+pub use pattern::PatternDatabase;
+pub use pruning::{MovePruner, MovePruning};
+pub use stats::SearchStats;
 pub use utils::{ColoredText, Config};
+pub use verify::{verify, VerifyError};
 pub use visualization::{print_side_by_side, write_results_to_file, MethodState};
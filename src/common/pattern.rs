@@ -0,0 +1,173 @@
+use super::{Board, Tile, WILDCARD};
+use std::collections::VecDeque;
+
+/// Largest board pattern databases are built for; a 5x5 group table would
+/// need 25^5 entries.
+pub const MAX_PATTERN_SIZE: usize = 4;
+
+// Tiles per group. Four keeps a 4x4 table at 16^5 entries during the build
+const GROUP_SIZE: usize = 4;
+const UNREACHED: u8 = u8::MAX;
+
+// Fewest moves of the group's own tiles from any placement of them to their
+// goal cells, indexed by the cells they occupy in base `cells`
+struct GroupTable {
+    tiles: Vec<Tile>,
+    distances: Vec<u8>,
+}
+
+/// Additive pattern databases for the goals of a board. The goal's tiles are
+/// split into disjoint groups, and each group gets a table of the fewest
+/// moves of its own tiles that bring it home, whatever the other tiles do.
+/// Disjoint groups count disjoint moves, so the sum over groups is
+/// admissible, and it is never below the Manhattan distance.
+pub struct PatternDatabase {
+    size: usize,
+    goal_states: Vec<Vec<Tile>>,
+    blocked: Vec<bool>,
+    // One set of group tables per goal
+    goals: Vec<Vec<GroupTable>>,
+}
+
+impl PatternDatabase {
+    /// `None` for boards larger than `MAX_PATTERN_SIZE`.
+    pub fn build(board: &Board) -> Option<Self> {
+        let size = board.get_size();
+        if size > MAX_PATTERN_SIZE {
+            return None;
+        }
+        let blocked: Vec<bool> = (0..size * size).map(|pos| board.is_blocked(pos)).collect();
+        let goals = board
+            .goal_states()
+            .iter()
+            .map(|goal| {
+                let mut tiles: Vec<Tile> = goal
+                    .iter()
+                    .copied()
+                    .filter(|&tile| tile != 0 && tile != WILDCARD)
+                    .collect();
+                tiles.sort();
+                tiles
+                    .chunks(GROUP_SIZE)
+                    .map(|group| GroupTable::build(group, goal, size, &blocked))
+                    .collect()
+            })
+            .collect();
+        Some(PatternDatabase {
+            size,
+            goal_states: board.goal_states().to_vec(),
+            blocked,
+            goals,
+        })
+    }
+
+    /// Whether the tables were built for the goals and blocked cells of `board`.
+    pub fn covers(&self, board: &Board) -> bool {
+        board.get_size() == self.size
+            && board.goal_states() == self.goal_states
+            && (0..self.size * self.size).all(|pos| board.is_blocked(pos) == self.blocked[pos])
+    }
+
+    /// Lower bound on the moves from `board` to the nearest goal.
+    pub fn evaluate(&self, board: &Board) -> u32 {
+        let cells = self.size * self.size;
+        let mut position = vec![0; cells];
+        for (pos, &tile) in board.tiles().iter().enumerate() {
+            position[tile as usize] = pos;
+        }
+        self.goals
+            .iter()
+            .map(|groups| {
+                groups
+                    .iter()
+                    .map(|group| {
+                        let index = group
+                            .tiles
+                            .iter()
+                            .fold(0, |index, &tile| index * cells + position[tile as usize]);
+                        group.distances[index] as u32
+                    })
+                    .sum()
+            })
+            .min()
+            .unwrap_or(0)
+    }
+}
+
+impl GroupTable {
+    // 0-1 breadth-first search back from the goal over placements of the
+    // group and the blank: sliding a group tile costs 1, any other tile 0.
+    // The blank may start anywhere, which only weakens the bound slightly.
+    fn build(group: &[Tile], goal: &[Tile], size: usize, blocked: &[bool]) -> Self {
+        let cells = size * size;
+        let k = group.len();
+        let states = cells.pow(k as u32 + 1);
+        let mut distances = vec![UNREACHED; states];
+        let mut queue = VecDeque::new();
+
+        let home: Vec<usize> = group
+            .iter()
+            .map(|&tile| goal.iter().position(|&t| t == tile).unwrap())
+            .collect();
+        let placement = home.iter().fold(0, |index, &pos| index * cells + pos);
+        for blank in (0..cells).filter(|&pos| !blocked[pos] && !home.contains(&pos)) {
+            let state = placement * cells + blank;
+            distances[state] = 0;
+            queue.push_back(state);
+        }
+
+        let mut positions = vec![0; k];
+        while let Some(state) = queue.pop_front() {
+            let distance = distances[state];
+            let blank = state % cells;
+            let mut rest = state / cells;
+            for i in (0..k).rev() {
+                positions[i] = rest % cells;
+                rest /= cells;
+            }
+
+            let (row, col) = (blank / size, blank % size);
+            let neighbours = [
+                (row > 0).then(|| blank - size),
+                (row + 1 < size).then(|| blank + size),
+                (col > 0).then(|| blank - 1),
+                (col + 1 < size).then(|| blank + 1),
+            ];
+            for next in neighbours.into_iter().flatten() {
+                if blocked[next] {
+                    continue;
+                }
+                match positions.iter().position(|&pos| pos == next) {
+                    Some(i) => {
+                        // A group tile slides into the blank's cell
+                        let mut moved = positions.clone();
+                        moved[i] = blank;
+                        let placement = moved.iter().fold(0, |index, &pos| index * cells + pos);
+                        let child = placement * cells + next;
+                        if distances[child] > distance + 1 {
+                            distances[child] = distance + 1;
+                            queue.push_back(child);
+                        }
+                    }
+                    None => {
+                        let child = state - blank + next;
+                        if distances[child] > distance {
+                            distances[child] = distance;
+                            queue.push_front(child);
+                        }
+                    }
+                }
+            }
+        }
+
+        // The blank's cell is not part of the pattern: keep the best over it
+        let distances = distances
+            .chunks(cells)
+            .map(|by_blank| by_blank.iter().copied().min().unwrap())
+            .collect();
+        GroupTable {
+            tiles: group.to_vec(),
+            distances,
+        }
+    }
+}
@@ -0,0 +1,256 @@
+use super::{Board, Direction};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::OnceLock;
+
+/// Longest move sequence the learned automaton knows about. Sliding-tile
+/// cycles start at 12 moves (three tiles rotated around a 2x2 square).
+pub const AUTOMATON_DEPTH: usize = 12;
+
+// Transition into a state that ends a redundant sequence
+const PRUNED: u32 = u32::MAX;
+
+// Start state accepts anything, state 1 + d means "last move was d"
+const NO_PRUNING: [[u32; 4]; 1] = [[0, 0, 0, 0]];
+const INVERSE: [[u32; 4]; 5] = [
+    [1, 2, 3, 4],
+    [1, PRUNED, 3, 4],
+    [PRUNED, 2, 3, 4],
+    [1, 2, 3, PRUNED],
+    [1, 2, PRUNED, 4],
+];
+
+fn index(dir: Direction) -> usize {
+    match dir {
+        Direction::Up => 0,
+        Direction::Down => 1,
+        Direction::Left => 2,
+        Direction::Right => 3,
+    }
+}
+
+/// Which moves a depth-first search is allowed to skip.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MovePruning {
+    /// Every legal move is generated
+    None,
+    /// Never undo the previous move
+    #[default]
+    Inverse,
+    /// Skip any sequence of up to `AUTOMATON_DEPTH` moves that has an earlier
+    /// equivalent, as found by an automaton learned from short cycles
+    Automaton,
+}
+
+/// Finite-state machine fed with the moves along the current path. A move
+/// that would end a redundant sequence has no transition and can be pruned
+/// without losing any optimal solution.
+#[derive(Clone, Copy, Debug)]
+pub struct MovePruner {
+    transitions: &'static [[u32; 4]],
+}
+
+impl Default for MovePruner {
+    fn default() -> Self {
+        MovePruner {
+            transitions: &INVERSE,
+        }
+    }
+}
+
+impl MovePruner {
+    /// The learned automaton only applies to unit move costs: an equivalent
+    /// sequence of the same length may slide more expensive tiles.
+    pub fn for_board(pruning: MovePruning, board: &Board) -> Self {
+        let transitions: &'static [[u32; 4]] = match pruning {
+            MovePruning::None => &NO_PRUNING,
+            MovePruning::Automaton if board.get_move_cost().is_unit() => learned_automaton(),
+            MovePruning::Inverse | MovePruning::Automaton => &INVERSE,
+        };
+        MovePruner { transitions }
+    }
+
+    pub fn start(&self) -> u32 {
+        0
+    }
+
+    /// State after `dir`, or `None` if the move should be pruned.
+    pub fn step(&self, state: u32, dir: Direction) -> Option<u32> {
+        let next = self.transitions[state as usize][index(dir)];
+        (next != PRUNED).then_some(next)
+    }
+
+    pub fn states(&self) -> usize {
+        self.transitions.len()
+    }
+}
+
+fn learned_automaton() -> &'static [[u32; 4]] {
+    static AUTOMATON: OnceLock<Vec<[u32; 4]>> = OnceLock::new();
+    AUTOMATON.get_or_init(|| build_automaton(&redundant_sequences(AUTOMATON_DEPTH)))
+}
+
+// Effect of a move sequence on an unbounded board, with the blank starting
+// at the origin: where the blank ends up and which tiles left their cell
+type Cell = (i8, i8);
+type Effect = (Cell, Vec<(Cell, Cell)>);
+
+#[derive(Clone)]
+struct Walk {
+    moves: Vec<u8>,
+    // Two bits per move, the latest in the lowest bits
+    packed: u32,
+    blank: Cell,
+    // (current cell, starting cell) of every displaced tile, sorted
+    moved: Vec<(Cell, Cell)>,
+    // Cells the blank passed through, sorted
+    visited: Vec<Cell>,
+}
+
+impl Walk {
+    fn step(&self, dir: u8) -> Walk {
+        let (row, col) = self.blank;
+        let target = match dir {
+            0 => (row - 1, col),
+            1 => (row + 1, col),
+            2 => (row, col - 1),
+            _ => (row, col + 1),
+        };
+        let mut walk = self.clone();
+        let tile = match walk.moved.binary_search_by_key(&target, |&(cell, _)| cell) {
+            Ok(index) => walk.moved.remove(index).1,
+            Err(_) => target,
+        };
+        if tile != self.blank {
+            let index = walk
+                .moved
+                .binary_search_by_key(&self.blank, |&(cell, _)| cell)
+                .unwrap_err();
+            walk.moved.insert(index, (self.blank, tile));
+        }
+        if let Err(index) = walk.visited.binary_search(&target) {
+            walk.visited.insert(index, target);
+        }
+        walk.moves.push(dir);
+        walk.packed = walk.packed << 2 | dir as u32;
+        walk.blank = target;
+        walk
+    }
+
+    fn effect(&self) -> Effect {
+        (self.blank, self.moved.clone())
+    }
+}
+
+fn is_subset(small: &[Cell], large: &[Cell]) -> bool {
+    small.iter().all(|cell| large.binary_search(cell).is_ok())
+}
+
+// Breadth-first over move sequences in shortlex order. A sequence is redundant
+// when an earlier one has the same effect and keeps the blank on a subset of
+// its cells, so that it is legal wherever the redundant one is. Any path
+// containing a redundant sequence can then be rewritten into an earlier path
+// of no greater length, so the earliest optimal path is never pruned.
+fn redundant_sequences(depth: usize) -> Vec<Vec<u8>> {
+    let mut redundant: Vec<Vec<u8>> = Vec::new();
+    // (length, packed moves) of every redundant sequence
+    let mut suffixes: HashSet<(usize, u32)> = HashSet::new();
+    let root = Walk {
+        moves: Vec::new(),
+        packed: 0,
+        blank: (0, 0),
+        moved: Vec::new(),
+        visited: vec![(0, 0)],
+    };
+    let mut seen: HashMap<Effect, Vec<Vec<Cell>>> = HashMap::new();
+    seen.insert(root.effect(), vec![root.visited.clone()]);
+    let mut frontier = vec![root];
+
+    for level in 1..=depth {
+        let mut next = Vec::new();
+        for walk in &frontier {
+            for dir in 0..4 {
+                // Undoing the last move is always redundant, no need to look it up
+                if walk.moves.last() == Some(&(dir ^ 1)) {
+                    if suffixes.insert((2, (dir as u32 ^ 1) << 2 | dir as u32)) {
+                        redundant.push(vec![dir ^ 1, dir]);
+                    }
+                    continue;
+                }
+                let child = walk.step(dir);
+                // Already pruned through a shorter redundant suffix
+                let len = child.moves.len();
+                if (2..len).any(|k| suffixes.contains(&(k, child.packed & ((1 << (2 * k)) - 1)))) {
+                    continue;
+                }
+                let earlier = seen.entry(child.effect()).or_default();
+                if earlier.iter().any(|cells| is_subset(cells, &child.visited)) {
+                    suffixes.insert((len, child.packed));
+                    redundant.push(child.moves);
+                } else {
+                    earlier.push(child.visited.clone());
+                    // Nothing is extended past the last level
+                    if level < depth {
+                        next.push(child);
+                    }
+                }
+            }
+        }
+        frontier = next;
+    }
+
+    redundant
+}
+
+// Aho-Corasick automaton over the redundant sequences: a transition is pruned
+// when the path so far ends in one of them
+fn build_automaton(patterns: &[Vec<u8>]) -> Vec<[u32; 4]> {
+    let mut children: Vec<[u32; 4]> = vec![[PRUNED; 4]];
+    let mut terminal = vec![false];
+    for pattern in patterns {
+        let mut state = 0;
+        for &dir in pattern {
+            let slot = children[state][dir as usize];
+            state = if slot == PRUNED {
+                children.push([PRUNED; 4]);
+                terminal.push(false);
+                let new = children.len() - 1;
+                children[state][dir as usize] = new as u32;
+                new
+            } else {
+                slot as usize
+            };
+        }
+        terminal[state] = true;
+    }
+
+    let mut transitions = children.clone();
+    let mut fail = vec![0; children.len()];
+    let mut queue = VecDeque::new();
+    for dir in 0..4 {
+        match children[0][dir] {
+            PRUNED => transitions[0][dir] = 0,
+            child => queue.push_back(child as usize),
+        }
+    }
+    while let Some(state) = queue.pop_front() {
+        terminal[state] |= terminal[fail[state]];
+        for dir in 0..4 {
+            match children[state][dir] {
+                PRUNED => transitions[state][dir] = transitions[fail[state]][dir],
+                child => {
+                    fail[child as usize] = transitions[fail[state]][dir] as usize;
+                    queue.push_back(child as usize);
+                }
+            }
+        }
+    }
+
+    for row in &mut transitions {
+        for next in row.iter_mut() {
+            if terminal[*next as usize] {
+                *next = PRUNED;
+            }
+        }
+    }
+    transitions
+}
@@ -0,0 +1,12 @@
+/// Counters collected while a solver explores the state space.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    pub nodes_expanded: usize,
+    pub nodes_generated: usize,
+    /// Expansions of a state that had already been expanded and forgotten
+    pub re_expansions: usize,
+    pub max_frontier: usize,
+    pub iterations: usize,
+    /// Moves skipped by the move pruner without generating the child
+    pub pruned_moves: usize,
+}
@@ -0,0 +1,116 @@
+use std::fmt;
+
+use super::{matches_goal, Board, Direction, Tile};
+
+/// Why a move sequence does not solve a puzzle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VerifyError {
+    /// A goal has a different number of cells than the board
+    GoalSizeMismatch { expected: usize, found: usize },
+    /// The move at `index` (from 0) leaves the board or enters a blocked cell
+    IllegalMove { index: usize, direction: Direction },
+    /// Every move is legal but the last board matches none of the goals
+    GoalNotReached { reached: Board },
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyError::GoalSizeMismatch { expected, found } => write!(
+                f,
+                "the goal has {} cells but the board has {}",
+                found, expected
+            ),
+            VerifyError::IllegalMove { index, direction } => {
+                write!(f, "move {} ({:?}) is not possible", index + 1, direction)
+            }
+            VerifyError::GoalNotReached { reached } => {
+                write!(f, "the moves end short of the goal:\n{}", reached)
+            }
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+/// Replays `moves` from `initial` with `Board::make_move` and checks that
+/// they end on one of `goals`, whose wildcards match any tile.
+pub fn verify(
+    initial: &Board,
+    goals: &[Vec<Tile>],
+    moves: &[Direction],
+) -> Result<(), VerifyError> {
+    let cells = initial.tiles().len();
+    if let Some(goal) = goals.iter().find(|goal| goal.len() != cells) {
+        return Err(VerifyError::GoalSizeMismatch {
+            expected: cells,
+            found: goal.len(),
+        });
+    }
+
+    let mut board = initial.clone();
+    for (index, &direction) in moves.iter().enumerate() {
+        board
+            .make_move(direction)
+            .map_err(|_| VerifyError::IllegalMove { index, direction })?;
+    }
+    if goals.iter().any(|goal| matches_goal(board.tiles(), goal)) {
+        Ok(())
+    } else {
+        Err(VerifyError::GoalNotReached { reached: board })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::input::parse_moves;
+
+    fn board() -> Board {
+        Board::new(vec![vec![1, 2, 3], vec![4, 0, 6], vec![7, 5, 8]])
+    }
+
+    #[test]
+    fn test_accepts_a_solution() {
+        let board = board();
+        let moves = parse_moves("DR").unwrap();
+        assert_eq!(verify(&board, board.goal_states(), &moves), Ok(()));
+        // Any of several goals will do
+        let goals = vec![
+            vec![1, 2, 3, 4, 5, 6, 7, 0, 8],
+            board.goal_states()[0].clone(),
+        ];
+        assert_eq!(verify(&board, &goals, &moves[..1]), Ok(()));
+    }
+
+    #[test]
+    fn test_reports_the_first_illegal_move() {
+        let board = board();
+        let moves = parse_moves("DDR").unwrap();
+        assert_eq!(
+            verify(&board, board.goal_states(), &moves),
+            Err(VerifyError::IllegalMove {
+                index: 1,
+                direction: Direction::Down
+            })
+        );
+    }
+
+    #[test]
+    fn test_reports_where_short_moves_end() {
+        let board = board();
+        let Err(VerifyError::GoalNotReached { reached }) =
+            verify(&board, board.goal_states(), &parse_moves("D").unwrap())
+        else {
+            panic!("one move does not solve the board");
+        };
+        assert_eq!(reached.get_row(2), vec![7, 0, 8]);
+        assert!(matches!(
+            verify(&board, &[vec![0; 4]], &[]),
+            Err(VerifyError::GoalSizeMismatch {
+                expected: 9,
+                found: 4
+            })
+        ));
+    }
+}
@@ -1,22 +1,47 @@
 use colored::*;
 use std::fs::File;
-use std::io::{self, Error, Write};
+use std::io::{self, Write};
 use std::time::Duration;
 
 use crate::{Board, SolutionInfo};
 
 pub struct MethodState {
+    pub name: String,
     pub board: Board,
     pub current_step: usize,
     pub solution: Option<SolutionInfo>,
     pub time_taken: Duration,
 }
 
+impl MethodState {
+    // Board after the first `moves` moves of the solution
+    fn board_after(&self, moves: usize) -> Option<Board> {
+        let solution = self.solution.as_ref()?;
+        let mut board = self.board.clone();
+        for &dir in &solution.moves[..moves.min(solution.moves.len())] {
+            board.make_move(dir).unwrap();
+        }
+        Some(board)
+    }
+}
+
 pub fn clear_screen() {
-    print!("[2J[1;1H");
+    print!("\x1B[2J\x1B[1;1H");
     io::stdout().flush().unwrap();
 }
 
+// Centres `text` in `width` columns; `visible` is its length without colour codes
+fn centred(text: &str, visible: usize, width: usize) -> String {
+    let left = width.saturating_sub(visible) / 2;
+    let right = width.saturating_sub(visible + left);
+    format!("{}{}{}", " ".repeat(left), text, " ".repeat(right))
+}
+
+fn border(left: &str, middle: &str, right: &str, columns: usize, width: usize) -> String {
+    let line = vec!["═".repeat(width); columns].join(middle);
+    format!("{}{}{}", left, line, right)
+}
+
 pub fn print_side_by_side(states: &[&MethodState], step: usize) {
     clear_screen();
     println!(
@@ -24,20 +49,29 @@ pub fn print_side_by_side(states: &[&MethodState], step: usize) {
         "=== N-Puzzle Solver Visualization ===".blue().bold()
     );
 
-    let methods = ["DFS", "BFS", "A*"];
+    let size = states.first().map_or(3, |state| state.board.get_size());
+    let cell = (size * size - 1).to_string().len() + 1;
+    let width = 19.max(size * cell + 4);
     let separator = "║".bright_cyan();
 
     // Colorful header with borders
     println!(
         "{}",
-        "╔═══════════════════╦═══════════════════╦═══════════════════╗".bright_cyan()
+        border("╔", "╦", "╗", states.len(), width).bright_cyan()
     );
 
     // Print method names
     print!("{}", separator);
-    for (i, method) in methods.iter().enumerate() {
-        print!("{:^19}", method.yellow());
-        if i < methods.len() - 1 {
+    for (i, state) in states.iter().enumerate() {
+        print!(
+            "{}",
+            centred(
+                &state.name.yellow().to_string(),
+                state.name.chars().count(),
+                width
+            )
+        );
+        if i < states.len() - 1 {
             print!("{}", separator);
         }
     }
@@ -45,36 +79,40 @@ pub fn print_side_by_side(states: &[&MethodState], step: usize) {
 
     // Top separator
     println!(
-        "{} ",
-        "╠═══════════════════╬═══════════════════╬═══════════════════╣".bright_cyan()
+        "{}",
+        border("╠", "╬", "╣", states.len(), width).bright_cyan()
     );
     // Print puzzle state
-    for i in 0..3 {
+    for i in 0..size {
         print!("{}", separator);
         for (j, state) in states.iter().enumerate() {
-            let board_str = if let Some(ref solution) = state.solution {
-                if step < solution.moves.len() {
-                    let mut board = state.board.clone();
-                    for k in 0..=step {
-                        board.make_move(solution.moves[k].clone()).unwrap();
-                    }
-                    let row = board.get_row(i);
-                    let nums: Vec<String> = row
+            let board_str = match state.solution {
+                Some(ref solution) if step < solution.moves.len() => {
+                    let board = state.board_after(step + 1).unwrap();
+                    let nums: Vec<String> = board
+                        .get_row(i)
                         .iter()
-                        .map(|&n| {
+                        .enumerate()
+                        .map(|(col, &n)| {
+                            let text = if n == 0 {
+                                "_".to_string()
+                            } else {
+                                n.to_string()
+                            };
+                            let text = format!("{:>1$}", text, cell);
                             if n == 0 {
-                                "_".red().to_string()
+                                text.red().to_string()
+                            } else if board.is_blocked(i * size + col) {
+                                text.black().on_white().to_string()
                             } else {
-                                n.to_string().green().to_string()
+                                text.green().to_string()
                             }
                         })
                         .collect();
-                    format!("    {}    {}    {}    ", nums[0], nums[1], nums[2])
-                } else {
-                    format!("{:^19}", "[Complete]".bright_blue())
+                    centred(&nums.concat(), size * cell, width)
                 }
-            } else {
-                format!("{:^19}", "[No Solution]".bright_red())
+                Some(_) => centred(&"[Complete]".bright_blue().to_string(), 10, width),
+                None => centred(&"[No Solution]".bright_red().to_string(), 13, width),
             };
             print!("{}", board_str);
             if j < states.len() - 1 {
@@ -86,8 +124,8 @@ pub fn print_side_by_side(states: &[&MethodState], step: usize) {
 
     // Middle separator
     println!(
-        "{} ",
-        "╠═══════════════════╬═══════════════════╬═══════════════════╣".bright_cyan()
+        "{}",
+        border("╠", "╬", "╣", states.len(), width).bright_cyan()
     );
 
     // Print current moves
@@ -102,7 +140,7 @@ pub fn print_side_by_side(states: &[&MethodState], step: usize) {
         } else {
             "No solution".bright_red()
         };
-        print!("{:^19}", move_str);
+        print!("{:^1$}", move_str, width);
         if j < states.len() - 1 {
             print!("{}", separator);
         }
@@ -111,18 +149,36 @@ pub fn print_side_by_side(states: &[&MethodState], step: usize) {
 
     // Bottom separator
     println!(
-        "{} ",
-        "╠═══════════════════╬═══════════════════╬═══════════════════╣".bright_cyan()
+        "{}",
+        border("╠", "╬", "╣", states.len(), width).bright_cyan()
     );
     // Print stats
     print!("{}", separator);
     for (j, state) in states.iter().enumerate() {
-        let stats = if let Some(ref solution) = state.solution {
+        let stats = if state.solution.is_some() {
             format!("Time: {:?}", state.time_taken).yellow()
         } else {
             "Failed".bright_red()
         };
-        print!("{:^19}", stats);
+        print!("{:^1$}", stats, width);
+        if j < states.len() - 1 {
+            print!("{}", separator);
+        }
+    }
+    println!("{}", separator);
+
+    // Grade against the optimal length
+    print!("{}", separator);
+    for (j, state) in states.iter().enumerate() {
+        let grade = match state.solution {
+            Some(ref solution) => centred(
+                &solution.quality.to_colored_string(),
+                solution.quality.name().len(),
+                width,
+            ),
+            None => " ".repeat(width),
+        };
+        print!("{}", grade);
         if j < states.len() - 1 {
             print!("{}", separator);
         }
@@ -132,52 +188,66 @@ pub fn print_side_by_side(states: &[&MethodState], step: usize) {
     // Final border
     println!(
         "{}",
-        "╚═══════════════════╩═══════════════════╩═══════════════════╝".bright_cyan()
+        border("╚", "╩", "╝", states.len(), width).bright_cyan()
     );
 
     io::stdout().flush().unwrap();
 }
 
+// One markdown row per board row, each method's board in its own cell
+fn write_board_rows(
+    file: &mut File,
+    states: &[&MethodState],
+    size: usize,
+    board_at: impl Fn(&MethodState) -> Result<Board, &'static str>,
+) -> io::Result<()> {
+    let divider = vec!["---"; size].join("|");
+    writeln!(file, "|{}|", divider)?;
+    for i in 0..size {
+        let mut row_str = String::new();
+        for state in states.iter() {
+            match board_at(state) {
+                Ok(board) => {
+                    let cells: Vec<String> = board
+                        .get_row(i)
+                        .iter()
+                        .map(|&n| {
+                            if n == 0 {
+                                "_".to_string()
+                            } else {
+                                n.to_string()
+                            }
+                        })
+                        .collect();
+                    row_str.push_str(&format!("| {} |", cells.join(" ")));
+                }
+                Err(placeholder) => row_str.push_str(&format!("| {} |", placeholder)),
+            }
+        }
+        writeln!(file, "{}", row_str)?;
+    }
+    writeln!(file, "|{}|", divider)
+}
+
 pub fn write_results_to_file(states: &[&MethodState]) -> io::Result<()> {
     let mut file = File::create("results.md")?;
-    let methods = ["DFS", "BFS", "A*"];
+    let size = states.first().map_or(3, |state| state.board.get_size());
 
     // Write header with proper markdown table formatting
+    let names: Vec<String> = states
+        .iter()
+        .map(|state| format!(" {:<18} ", state.name))
+        .collect();
+    writeln!(file, "|{}|", names.join("|"))?;
     writeln!(
         file,
-        "| {:<18} | {:<18} | {:<18} |",
-        methods[0], methods[1], methods[2]
+        "|{}|",
+        vec![format!(":{:-<18}:", ""); states.len()].join("|")
     )?;
-    writeln!(file, "|:{:-<18}:|:{:-<18}:|:{:-<18}:|", "", "", "")?;
 
     // Write initial state with better formatting
     writeln!(file, "\n### Initial State\n")?;
-    writeln!(file, "|---|---|---|")?;
-    for i in 0..3 {
-        let mut row_str = String::new();
-        for state in states.iter() {
-            let row = state.board.get_row(i);
-            let val0 = if row[0] == 0 {
-                "_"
-            } else {
-                &row[0].to_string()
-            };
-            let val1 = if row[1] == 0 {
-                "_"
-            } else {
-                &row[1].to_string()
-            };
-            let val2 = if row[2] == 0 {
-                "_"
-            } else {
-                &row[2].to_string()
-            };
-            let cells = format!("| {} {} {} |", val0, val1, val2);
-            row_str.push_str(&cells);
-        }
-        writeln!(file, "{}", row_str)?;
-    }
-    writeln!(file, "|---|---|---|")?;
+    write_board_rows(&mut file, states, size, |state| Ok(state.board.clone()))?;
 
     // Write solution steps with better formatting
     let max_steps = states
@@ -189,44 +259,13 @@ pub fn write_results_to_file(states: &[&MethodState]) -> io::Result<()> {
 
     for step in 0..max_steps {
         writeln!(file, "\n### Step {}\n", step + 1)?;
-        writeln!(file, "|---|---|---|")?;
-        for i in 0..3 {
-            let mut row_str = String::new();
-            for state in states.iter() {
-                if let Some(ref solution) = state.solution {
-                    if step < solution.moves.len() {
-                        let mut board = state.board.clone();
-                        for k in 0..=step {
-                            board.make_move(solution.moves[k].clone()).unwrap();
-                        }
-                        let row = board.get_row(i);
-                        let val0 = if row[0] == 0 {
-                            "_"
-                        } else {
-                            &row[0].to_string()
-                        };
-                        let val1 = if row[1] == 0 {
-                            "_"
-                        } else {
-                            &row[1].to_string()
-                        };
-                        let val2 = if row[2] == 0 {
-                            "_"
-                        } else {
-                            &row[2].to_string()
-                        };
-                        let cells = format!("| {} {} {} |", val0, val1, val2);
-                        row_str.push_str(&cells);
-                    } else {
-                        row_str.push_str("| [Complete] |");
-                    }
-                } else {
-                    row_str.push_str("| [No solution] |");
-                }
+        write_board_rows(&mut file, states, size, |state| match state.solution {
+            Some(ref solution) if step < solution.moves.len() => {
+                Ok(state.board_after(step + 1).unwrap())
             }
-            writeln!(file, "{}", row_str)?;
-        }
-        writeln!(file, "|---|---|---|")?;
+            Some(_) => Err("[Complete]"),
+            None => Err("[No solution]"),
+        })?;
 
         // Write moves for this step
         let mut move_str = String::new();
@@ -247,33 +286,35 @@ pub fn write_results_to_file(states: &[&MethodState]) -> io::Result<()> {
 
     // Write final stats with better formatting
     writeln!(file, "\n### Final Stats\n")?;
-    writeln!(file, "| Metric | DFS | BFS | A* |")?;
-    writeln!(file, "|:--|:--|:--|:--|")?;
+    let names: Vec<&str> = states.iter().map(|state| state.name.as_str()).collect();
+    writeln!(file, "| Metric | {} |", names.join(" | "))?;
+    writeln!(file, "|:--|{}", ":--|".repeat(states.len()))?;
 
     // Write steps
-    let steps_str = format!(
-        "| Steps | {} | {} | {} |",
-        states[0]
-            .solution
-            .as_ref()
-            .map_or("Failed".to_string(), |s| s.moves.len().to_string()),
-        states[1]
-            .solution
-            .as_ref()
-            .map_or("Failed".to_string(), |s| s.moves.len().to_string()),
-        states[2]
-            .solution
-            .as_ref()
-            .map_or("Failed".to_string(), |s| s.moves.len().to_string())
-    );
-    writeln!(file, "{}", steps_str)?;
+    let steps: Vec<String> = states
+        .iter()
+        .map(|state| {
+            state
+                .solution
+                .as_ref()
+                .map_or("Failed".to_string(), |s| s.moves.len().to_string())
+        })
+        .collect();
+    writeln!(file, "| Steps | {} |", steps.join(" | "))?;
 
     // Write time
-    let time_str = format!(
-        "| Time | {:?} | {:?} | {:?} |",
-        states[0].time_taken, states[1].time_taken, states[2].time_taken
-    );
-    writeln!(file, "{}", time_str)?;
+    let times: Vec<String> = states
+        .iter()
+        .map(|state| format!("{:?}", state.time_taken))
+        .collect();
+    writeln!(file, "| Time | {} |", times.join(" | "))?;
+
+    // Write grades
+    let grades: Vec<&str> = states
+        .iter()
+        .map(|state| state.solution.as_ref().map_or("-", |s| s.quality.name()))
+        .collect();
+    writeln!(file, "| Quality | {} |", grades.join(" | "))?;
 
     Ok(())
 }
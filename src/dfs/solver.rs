@@ -1,36 +1,24 @@
-use crate::common::{Board, Direction};
+use crate::common::{matches_goal, Board, Direction, MovePruner, MovePruning, SearchStats, Tile};
 use crate::{SolutionInfo, Solver};
 use std::collections::HashSet;
 
 // State struct for DFS
 #[derive(Clone)]
 struct State {
-    state: Vec<u8>,
-    goal_state: Vec<u8>,
+    state: Vec<Tile>,
+    goal_states: Vec<Vec<Tile>>,
+    blocked: Vec<bool>,
     blank_pos: usize,
     size: usize,
+    pruner_state: u32,
     path: Vec<Direction>,
 }
 
 impl State {
-    fn new(board: &Board) -> Self {
-        let state: Vec<u8> = board
-            .get_state()
-            .into_iter()
-            .flat_map(|row| row.into_iter())
-            .collect();
-        let goal_state = board.get_goal_state();
-        State {
-            state,
-            goal_state,
-            blank_pos: 0, // Will be set in initialization
-            size: board.get_size(),
-            path: Vec::new(),
-        }
-    }
-
     fn is_goal(&self) -> bool {
-        self.state == self.goal_state
+        self.goal_states
+            .iter()
+            .any(|goal| matches_goal(&self.state, goal))
     }
 
     fn get_possible_moves(&self) -> Vec<Direction> {
@@ -54,7 +42,7 @@ impl State {
         moves
     }
 
-    fn make_move(&self, dir: Direction) -> Option<State> {
+    fn make_move(&self, dir: Direction, pruner_state: u32) -> Option<State> {
         let row = self.blank_pos / self.size;
         let col = self.blank_pos % self.size;
 
@@ -65,6 +53,9 @@ impl State {
             Direction::Right if col < self.size - 1 => self.blank_pos + 1,
             _ => return None,
         };
+        if self.blocked[new_pos] {
+            return None;
+        }
 
         let mut new_state = self.state.clone();
         new_state.swap(self.blank_pos, new_pos);
@@ -74,9 +65,11 @@ impl State {
 
         Some(State {
             state: new_state,
-            goal_state: self.goal_state.clone(),
+            goal_states: self.goal_states.clone(),
+            blocked: self.blocked.clone(),
             blank_pos: new_pos,
             size: self.size,
+            pruner_state,
             path: new_path,
         })
     }
@@ -84,10 +77,16 @@ impl State {
 
 pub struct DFSSolver {
     initial_board: Board,
+    pruning: MovePruning,
 }
 
 impl DFSSolver {
-    fn find_blank_pos(state: &[u8]) -> usize {
+    pub fn with_pruning(mut self, pruning: MovePruning) -> Self {
+        self.pruning = pruning;
+        self
+    }
+
+    fn find_blank_pos(state: &[Tile]) -> usize {
         state.iter().position(|&x| x == 0).unwrap_or(0) // Should never happen with valid input
     }
 }
@@ -96,33 +95,41 @@ impl Solver for DFSSolver {
     fn new(initial: Board) -> Self {
         DFSSolver {
             initial_board: initial,
+            pruning: MovePruning::default(),
         }
     }
 
     fn new_with_goal(initial: Board) -> Self {
         DFSSolver {
             initial_board: initial,
+            pruning: MovePruning::default(),
         }
     }
 
     fn solve(&self, optimal_length: Option<usize>) -> Option<SolutionInfo> {
         let mut stack = Vec::new();
         let mut visited = HashSet::new();
+        let mut stats = SearchStats::default();
+        let pruner = MovePruner::for_board(self.pruning, &self.initial_board);
 
         // Initialize start state
-        let state: Vec<u8> = self
+        let state: Vec<Tile> = self
             .initial_board
             .get_state()
             .into_iter()
             .flat_map(|row| row.into_iter())
             .collect();
-        let goal_state = self.initial_board.get_goal_state();
+        let goal_states = self.initial_board.goal_states().to_vec();
 
         let initial_state = State {
             state: state.clone(),
-            goal_state,
+            goal_states,
+            blocked: (0..state.len())
+                .map(|pos| self.initial_board.is_blocked(pos))
+                .collect(),
             blank_pos: Self::find_blank_pos(&state),
             size: self.initial_board.get_size(),
+            pruner_state: pruner.start(),
             path: Vec::new(),
         };
 
@@ -132,14 +139,21 @@ impl Solver for DFSSolver {
                 .get_state()
                 .into_iter()
                 .flatten()
-                .collect::<Vec<u8>>(),
+                .collect::<Vec<Tile>>(),
         );
 
         let max_depth = 20; // Maximum search depth
 
         while let Some(current_state) = stack.pop() {
             if current_state.is_goal() {
-                return Some(SolutionInfo::new(current_state.path, optimal_length));
+                let cost = self.initial_board.path_cost(&current_state.path);
+                let goal = self.initial_board.goal_reached_by(&current_state.path);
+                return Some(
+                    SolutionInfo::new(current_state.path, optimal_length)
+                        .with_cost(cost)
+                        .with_goal(goal)
+                        .with_stats(stats),
+                );
             }
 
             if current_state.path.len() >= max_depth {
@@ -147,9 +161,15 @@ impl Solver for DFSSolver {
             }
 
             // Collect all possible next states
+            stats.nodes_expanded += 1;
             let mut next_states = Vec::new();
             for direction in current_state.get_possible_moves() {
-                if let Some(new_state) = current_state.make_move(direction) {
+                let Some(pruner_state) = pruner.step(current_state.pruner_state, direction) else {
+                    stats.pruned_moves += 1;
+                    continue;
+                };
+                if let Some(new_state) = current_state.make_move(direction, pruner_state) {
+                    stats.nodes_generated += 1;
                     if !visited.contains(&new_state.state) {
                         visited.insert(new_state.state.clone());
                         next_states.push(new_state);
@@ -158,8 +178,9 @@ impl Solver for DFSSolver {
             }
 
             // Sort by path length in descending order since we pop from the end
-            next_states.sort_by(|a, b| b.path.len().cmp(&a.path.len()));
+            next_states.sort_by_key(|s| std::cmp::Reverse(s.path.len()));
             stack.extend(next_states);
+            stats.max_frontier = stats.max_frontier.max(stack.len());
         }
 
         None
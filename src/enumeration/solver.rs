@@ -0,0 +1,293 @@
+use crate::common::{Board, Direction, Heuristic, SearchStats, Tile};
+use crate::{IDAStarSolver, SolutionInfo, Solver};
+use std::collections::{HashMap, HashSet};
+
+// Number of optimal continuations from each (state, cost so far) on the optimal DAG
+type PathCounts = HashMap<(Vec<Tile>, u32), u64>;
+
+/// Every optimal solution of a board, or the first `paths.len()` of them when
+/// there are more than the enumerator was allowed to list.
+#[derive(Clone, Debug)]
+pub struct SolutionSet {
+    initial_board: Board,
+    /// Cost shared by all optimal solutions
+    pub cost: u32,
+    /// Total number of optimal solutions, saturating at `u64::MAX`
+    pub count: u64,
+    pub paths: Vec<Vec<Direction>>,
+    pub stats: SearchStats,
+}
+
+impl SolutionSet {
+    pub fn is_complete(&self) -> bool {
+        self.paths.len() as u64 == self.count
+    }
+
+    /// Number of different tiles that slide along `path`.
+    pub fn distinct_tiles_moved(&self, path: &[Direction]) -> usize {
+        let mut board = self.initial_board.clone();
+        let mut tiles = HashSet::new();
+        for &dir in path {
+            let blank = board.blank_pos();
+            board.make_move(dir).unwrap();
+            // The moved tile now sits where the blank was
+            tiles.insert(board.tiles()[blank]);
+        }
+        tiles.len()
+    }
+
+    /// The listed path minimising `key`, earliest first on ties.
+    pub fn best_by<K: Ord>(&self, key: impl Fn(&[Direction]) -> K) -> Option<&[Direction]> {
+        self.paths
+            .iter()
+            .min_by_key(|path| key(path))
+            .map(Vec::as_slice)
+    }
+
+    /// The optimal solution moving the fewest distinct tiles. `None` when
+    /// the listing was capped: the set of tiles moved depends on the whole
+    /// path, so unlike the count it cannot be read off the memoised DAG.
+    pub fn fewest_tiles_moved(&self) -> Option<&[Direction]> {
+        if !self.is_complete() {
+            return None;
+        }
+        self.best_by(|path| self.distinct_tiles_moved(path))
+    }
+}
+
+/// Finds the optimal cost with IDA*, then counts and lists every path of that
+/// cost through a memoised search over states whose f-value stays within it.
+pub struct SolutionEnumerator {
+    initial_board: Board,
+    heuristic: Heuristic,
+    max_paths: usize,
+}
+
+impl SolutionEnumerator {
+    pub fn with_heuristic(mut self, heuristic: Heuristic) -> Self {
+        self.heuristic = heuristic;
+        self
+    }
+
+    /// Caps how many paths are listed; all of them are still counted.
+    pub fn with_max_paths(mut self, max_paths: usize) -> Self {
+        self.max_paths = max_paths;
+        self
+    }
+
+    pub fn enumerate(&self) -> Option<SolutionSet> {
+        let optimal = IDAStarSolver::new(self.initial_board.clone())
+            .with_heuristic(self.heuristic)
+            .solve(None)?;
+        let cost = optimal.total_cost;
+
+        let mut stats = SearchStats::default();
+        let mut counts = PathCounts::new();
+        let mut board = self.initial_board.clone();
+        let count = self.count(&mut board, 0, cost, &mut counts, &mut stats);
+
+        let mut paths = Vec::new();
+        self.collect(&mut board, 0, cost, &counts, &mut Vec::new(), &mut paths);
+
+        Some(SolutionSet {
+            initial_board: self.initial_board.clone(),
+            cost,
+            count,
+            paths,
+            stats,
+        })
+    }
+
+    fn count(
+        &self,
+        board: &mut Board,
+        g_cost: u32,
+        bound: u32,
+        counts: &mut PathCounts,
+        stats: &mut SearchStats,
+    ) -> u64 {
+        if g_cost + board.heuristic(self.heuristic) > bound {
+            return 0;
+        }
+        // Reaching the goal any cheaper would contradict the optimal cost
+        if board.is_goal() {
+            return (g_cost == bound) as u64;
+        }
+        let key = (board.tiles().to_vec(), g_cost);
+        if let Some(&count) = counts.get(&key) {
+            return count;
+        }
+
+        stats.nodes_expanded += 1;
+        let mut total: u64 = 0;
+        for dir in board.get_possible_moves() {
+            let Some(move_cost) = board.cost_of_move(dir) else {
+                continue;
+            };
+            board.make_move(dir).unwrap();
+            stats.nodes_generated += 1;
+            total =
+                total.saturating_add(self.count(board, g_cost + move_cost, bound, counts, stats));
+            board.make_move(dir.opposite()).unwrap();
+        }
+        counts.insert(key, total);
+        total
+    }
+
+    // Walks the counted DAG, only entering states with at least one optimal continuation
+    fn collect(
+        &self,
+        board: &mut Board,
+        g_cost: u32,
+        bound: u32,
+        counts: &PathCounts,
+        path: &mut Vec<Direction>,
+        paths: &mut Vec<Vec<Direction>>,
+    ) {
+        if paths.len() >= self.max_paths {
+            return;
+        }
+        if board.is_goal() {
+            if g_cost == bound {
+                paths.push(path.clone());
+            }
+            return;
+        }
+
+        for dir in board.get_possible_moves() {
+            let Some(move_cost) = board.cost_of_move(dir) else {
+                continue;
+            };
+            board.make_move(dir).unwrap();
+            let g_child = g_cost + move_cost;
+            let reachable = (board.is_goal() && g_child == bound)
+                || counts
+                    .get(&(board.tiles().to_vec(), g_child))
+                    .is_some_and(|&count| count > 0);
+            if reachable {
+                path.push(dir);
+                self.collect(board, g_child, bound, counts, path, paths);
+                path.pop();
+            }
+            board.make_move(dir.opposite()).unwrap();
+        }
+    }
+}
+
+impl Solver for SolutionEnumerator {
+    fn new(initial: Board) -> Self {
+        SolutionEnumerator {
+            initial_board: initial,
+            heuristic: Heuristic::default(),
+            max_paths: 10_000,
+        }
+    }
+
+    fn new_with_goal(initial: Board) -> Self {
+        SolutionEnumerator {
+            initial_board: initial,
+            heuristic: Heuristic::default(),
+            max_paths: 10_000,
+        }
+    }
+
+    /// Returns the optimal solution that moves the fewest distinct tiles, or
+    /// the best of the listed ones when there are more than `max_paths`.
+    fn solve(&self, optimal_length: Option<usize>) -> Option<SolutionInfo> {
+        let set = self.enumerate()?;
+        let path = set
+            .fewest_tiles_moved()
+            .or_else(|| set.best_by(|path| set.distinct_tiles_moved(path)))?
+            .to_vec();
+        let goal = self.initial_board.goal_reached_by(&path);
+        Some(
+            SolutionInfo::new(path, optimal_length)
+                .with_cost(set.cost)
+                .with_goal(goal)
+                .with_stats(set.stats),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DistanceTable;
+
+    // Optimal path count straight from the exact distance table
+    fn table_count(table: &DistanceTable, board: &Board) -> u64 {
+        let distance = table.distance(board).unwrap();
+        if distance == 0 {
+            return 1;
+        }
+        board
+            .get_possible_moves()
+            .into_iter()
+            .map(|dir| {
+                let mut next = board.clone();
+                next.make_move(dir).unwrap();
+                match table.distance(&next) {
+                    Some(d) if d + 1 == distance => table_count(table, &next),
+                    _ => 0,
+                }
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_counts_match_distance_table() {
+        let board = Board::new(vec![vec![0, 5, 6], vec![1, 2, 3], vec![4, 7, 8]]);
+        let table = DistanceTable::build(&board);
+        let set = SolutionEnumerator::new(board.clone()).enumerate().unwrap();
+
+        assert_eq!(set.count, 3);
+        assert_eq!(set.count, table_count(&table, &board));
+        assert!(set.is_complete());
+        for path in &set.paths {
+            let mut test_board = board.clone();
+            for &dir in path {
+                test_board.make_move(dir).unwrap();
+            }
+            assert!(test_board.is_goal());
+            assert_eq!(path.len() as u32, set.cost);
+        }
+    }
+
+    #[test]
+    fn test_listing_is_capped_but_count_is_not() {
+        let board = Board::new(vec![vec![8, 6, 7], vec![2, 5, 4], vec![3, 0, 1]]);
+        let set = SolutionEnumerator::new(board)
+            .with_heuristic(Heuristic::LinearConflict)
+            .with_max_paths(3)
+            .enumerate()
+            .unwrap();
+
+        assert_eq!(set.cost, 31);
+        assert_eq!(set.paths.len(), 3);
+        assert!(set.count > 3);
+        assert!(!set.is_complete());
+        assert_eq!(set.fewest_tiles_moved(), None);
+        assert!(set.best_by(|path| set.distinct_tiles_moved(path)).is_some());
+    }
+
+    #[test]
+    fn test_solve_picks_fewest_tiles_moved() {
+        let board = Board::new(vec![
+            vec![2, 3, 4, 8],
+            vec![9, 6, 7, 0],
+            vec![13, 5, 1, 11],
+            vec![14, 15, 10, 12],
+        ]);
+        let enumerator = SolutionEnumerator::new(board).with_heuristic(Heuristic::LinearConflict);
+        let set = enumerator.enumerate().unwrap();
+        let solution = enumerator.solve(None).unwrap();
+
+        assert_eq!(set.count, 4);
+        assert_eq!(solution.moves.len(), 24);
+        assert_eq!(set.distinct_tiles_moved(&solution.moves), 14);
+        assert!(set
+            .paths
+            .iter()
+            .any(|path| set.distinct_tiles_moved(path) == 15));
+    }
+}
@@ -0,0 +1,299 @@
+use crate::common::{Board, Direction, Heuristic, SearchLimits, SearchStats, Tile};
+use crate::{SolutionInfo, Solver};
+use std::collections::HashMap;
+use std::time::Instant;
+
+const NIL: usize = usize::MAX;
+
+// Link in the doubly linked fringe list
+struct Link {
+    board: Board,
+    prev: usize,
+    next: usize,
+}
+
+struct Cached {
+    g_cost: u32,
+    parent: Option<(Vec<Tile>, Direction)>,
+    fringe_slot: Option<usize>,
+    expanded: bool,
+}
+
+// The fringe ("now" and "later" lists merged into one), kept in visiting order
+struct Fringe {
+    links: Vec<Link>,
+    free: Vec<usize>,
+    head: usize,
+    len: usize,
+}
+
+impl Fringe {
+    fn new() -> Self {
+        Fringe {
+            links: Vec::new(),
+            free: Vec::new(),
+            head: NIL,
+            len: 0,
+        }
+    }
+
+    // Inserts right after `after`, or at the front when `after` is NIL
+    fn insert_after(&mut self, after: usize, board: Board) -> usize {
+        let next = if after == NIL {
+            self.head
+        } else {
+            self.links[after].next
+        };
+        let link = Link {
+            board,
+            prev: after,
+            next,
+        };
+        let slot = match self.free.pop() {
+            Some(slot) => {
+                self.links[slot] = link;
+                slot
+            }
+            None => {
+                self.links.push(link);
+                self.links.len() - 1
+            }
+        };
+
+        if after == NIL {
+            self.head = slot;
+        } else {
+            self.links[after].next = slot;
+        }
+        if next != NIL {
+            self.links[next].prev = slot;
+        }
+        self.len += 1;
+        slot
+    }
+
+    fn remove(&mut self, slot: usize) {
+        let (prev, next) = (self.links[slot].prev, self.links[slot].next);
+        if prev == NIL {
+            self.head = next;
+        } else {
+            self.links[prev].next = next;
+        }
+        if next != NIL {
+            self.links[next].prev = prev;
+        }
+        self.free.push(slot);
+        self.len -= 1;
+    }
+}
+
+/// Fringe search: IDA*-style thresholds, but the frontier of each iteration
+/// is kept so the next one resumes from it instead of starting over.
+pub struct FringeSolver {
+    initial_board: Board,
+    heuristic: Heuristic,
+    limits: SearchLimits,
+}
+
+impl FringeSolver {
+    pub fn with_heuristic(mut self, heuristic: Heuristic) -> Self {
+        self.heuristic = heuristic;
+        self
+    }
+
+    pub fn with_limits(mut self, limits: SearchLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    fn path_to(cache: &HashMap<Vec<Tile>, Cached>, goal: &[Tile]) -> Vec<Direction> {
+        let mut path = Vec::new();
+        let mut tiles = goal.to_vec();
+        while let Some((parent, dir)) = &cache[&tiles].parent {
+            path.push(*dir);
+            tiles = parent.clone();
+        }
+        path.reverse();
+        path
+    }
+}
+
+impl Solver for FringeSolver {
+    fn new(initial: Board) -> Self {
+        FringeSolver {
+            initial_board: initial,
+            heuristic: Heuristic::default(),
+            limits: SearchLimits::default(),
+        }
+    }
+
+    fn new_with_goal(initial: Board) -> Self {
+        FringeSolver {
+            initial_board: initial,
+            heuristic: Heuristic::default(),
+            limits: SearchLimits::default(),
+        }
+    }
+
+    fn solve(&self, optimal_length: Option<usize>) -> Option<SolutionInfo> {
+        // Without a reachable goal the cache would grow over the whole state space
+        if !self.initial_board.is_solvable() {
+            return None;
+        }
+
+        let started = Instant::now();
+        let mut stats = SearchStats::default();
+        let mut fringe = Fringe::new();
+        let mut cache = HashMap::new();
+
+        let root_slot = fringe.insert_after(NIL, self.initial_board.clone());
+        cache.insert(
+            self.initial_board.tiles().to_vec(),
+            Cached {
+                g_cost: 0,
+                parent: None,
+                fringe_slot: Some(root_slot),
+                expanded: false,
+            },
+        );
+        let mut f_limit = self.initial_board.heuristic(self.heuristic);
+
+        while fringe.len > 0 {
+            stats.iterations += 1;
+            let mut f_min = u32::MAX;
+            let mut cursor = fringe.head;
+
+            while cursor != NIL {
+                let board = fringe.links[cursor].board.clone();
+                let tiles = board.tiles().to_vec();
+                let g_cost = cache[&tiles].g_cost;
+
+                let f_cost = g_cost + board.heuristic(self.heuristic);
+                if f_cost > f_limit {
+                    f_min = f_min.min(f_cost);
+                    cursor = fringe.links[cursor].next;
+                    continue;
+                }
+                if board.is_goal() {
+                    let path = Self::path_to(&cache, &tiles);
+                    let cost = self.initial_board.path_cost(&path);
+                    let goal = self.initial_board.goal_reached_by(&path);
+                    return Some(
+                        SolutionInfo::new(path, optimal_length)
+                            .with_cost(cost)
+                            .with_goal(goal)
+                            .with_stats(stats),
+                    );
+                }
+
+                stats.nodes_expanded += 1;
+                if self.limits.exceeded(stats.nodes_expanded, started) {
+                    return None;
+                }
+                let cached = cache.get_mut(&tiles).expect("fringe states are cached");
+                if cached.expanded {
+                    stats.re_expansions += 1;
+                }
+                cached.expanded = true;
+
+                // Children go right after the current node, in move order, so they
+                // are visited within this same iteration
+                let mut insert_at = cursor;
+                for dir in board.get_possible_moves() {
+                    let Some(move_cost) = board.cost_of_move(dir) else {
+                        continue;
+                    };
+                    let mut child = board.clone();
+                    if child.make_move(dir).is_err() {
+                        continue;
+                    }
+                    stats.nodes_generated += 1;
+
+                    let child_g = g_cost + move_cost;
+                    let child_tiles = child.tiles().to_vec();
+                    if let Some(existing) = cache.get(&child_tiles) {
+                        if existing.g_cost <= child_g {
+                            continue;
+                        }
+                        if let Some(slot) = existing.fringe_slot {
+                            fringe.remove(slot);
+                        }
+                    }
+
+                    insert_at = fringe.insert_after(insert_at, child);
+                    let expanded = cache.get(&child_tiles).is_some_and(|c| c.expanded);
+                    cache.insert(
+                        child_tiles,
+                        Cached {
+                            g_cost: child_g,
+                            parent: Some((tiles.clone(), dir)),
+                            fringe_slot: Some(insert_at),
+                            expanded,
+                        },
+                    );
+                }
+
+                let next = fringe.links[cursor].next;
+                fringe.remove(cursor);
+                cache.get_mut(&tiles).expect("still cached").fringe_slot = None;
+                stats.max_frontier = stats.max_frontier.max(fringe.len);
+                cursor = next;
+            }
+
+            if f_min == u32::MAX {
+                break;
+            }
+            f_limit = f_min;
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AStarSolver, IDAStarSolver, MoveCost, UniformCostSolver};
+
+    #[test]
+    fn test_matches_astar_length() {
+        let board = Board::new(vec![vec![8, 6, 7], vec![2, 5, 4], vec![3, 0, 1]]);
+        let astar = AStarSolver::new(board.clone()).solve(None).unwrap();
+        let solution = FringeSolver::new(board.clone()).solve(None).unwrap();
+        assert_eq!(solution.moves.len(), astar.moves.len());
+
+        let mut test_board = board;
+        for dir in solution.moves {
+            test_board.make_move(dir).unwrap();
+        }
+        assert!(test_board.is_goal());
+    }
+
+    #[test]
+    fn test_expands_fewer_nodes_than_ida_star() {
+        let board = Board::new(vec![vec![4, 1, 2], vec![0, 8, 7], vec![6, 3, 5]]);
+        let fringe = FringeSolver::new(board.clone()).solve(None).unwrap();
+        let ida = IDAStarSolver::new(board).solve(None).unwrap();
+
+        assert_eq!(fringe.moves.len(), ida.moves.len());
+        assert!(fringe.stats.nodes_expanded < ida.stats.nodes_expanded);
+    }
+
+    #[test]
+    fn test_move_costs_match_uniform_cost_search() {
+        // The cheapest path takes 15 moves where 11 would do
+        let board = Board::new(vec![vec![1, 2, 3], vec![0, 5, 6], vec![7, 8, 4]])
+            .with_move_cost(MoveCost::FaceValue);
+        let ucs = UniformCostSolver::new(board.clone()).solve(None).unwrap();
+        let fringe = FringeSolver::new(board.clone()).solve(None).unwrap();
+        assert_eq!(fringe.total_cost, ucs.total_cost);
+        assert_eq!(board.path_cost(&fringe.moves), fringe.total_cost);
+        assert_eq!(board.goal_reached_by(&fringe.moves), Some(0));
+    }
+
+    #[test]
+    fn test_unsolvable_returns_none() {
+        let board = Board::new(vec![vec![2, 1], vec![3, 0]]);
+        assert!(FringeSolver::new(board).solve(None).is_none());
+    }
+}
@@ -0,0 +1,2 @@
+mod scramble;
+pub use scramble::PuzzleGenerator;
@@ -0,0 +1,224 @@
+use std::collections::HashSet;
+
+use crate::{Board, Direction, Hinter, Tile};
+
+// Stand-in for a zero seed, which would keep xorshift at zero forever
+const ZERO_SEED: u64 = 0x9E37_79B9_7F4A_7C15;
+
+// xorshift64*: small, fast and the same on every platform
+struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Rng {
+            state: if seed == 0 { ZERO_SEED } else { seed },
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    // Uniform in 0..n, rejecting the values that would favour small results
+    fn below(&mut self, n: usize) -> usize {
+        let n = n as u64;
+        let zone = u64::MAX - u64::MAX % n;
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return (value % n) as usize;
+            }
+        }
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/// Random solvable puzzles for the standard goal of a given size. Every
+/// puzzle is a function of the seed and of the calls made so far, so the
+/// same seed gives the same sequence of puzzles.
+pub struct PuzzleGenerator {
+    size: usize,
+    seed: u64,
+    rng: Rng,
+    hinter: Hinter,
+}
+
+impl PuzzleGenerator {
+    pub fn new(size: usize) -> Self {
+        assert!(size >= 2, "puzzles are at least 2x2");
+        PuzzleGenerator {
+            size,
+            seed: 0,
+            rng: Rng::new(0),
+            hinter: Hinter::new(),
+        }
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self.rng = Rng::new(seed);
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn goal(&self) -> Board {
+        Board::new(Board::standard_goal(self.size))
+    }
+
+    fn board(&self, tiles: &[i32]) -> Board {
+        Board::new(tiles.chunks(self.size).map(<[i32]>::to_vec).collect())
+    }
+
+    /// A solvable permutation, every one equally likely. Half of all
+    /// shuffles are unsolvable; swapping their first two tiles pairs each
+    /// of them with exactly one solvable permutation.
+    pub fn random_permutation(&mut self) -> Board {
+        let mut tiles: Vec<i32> = (0..(self.size * self.size) as i32).collect();
+        self.rng.shuffle(&mut tiles);
+        let board = self.board(&tiles);
+        if board.is_solvable() {
+            return board;
+        }
+        let mut occupied = (0..tiles.len()).filter(|&pos| tiles[pos] != 0);
+        let (first, second) = (occupied.next().unwrap(), occupied.next().unwrap());
+        tiles.swap(first, second);
+        self.board(&tiles)
+    }
+
+    /// The goal scrambled by `moves` random moves of the blank, never
+    /// undoing the move just made.
+    pub fn random_walk(&mut self, moves: usize) -> Board {
+        let mut board = self.goal();
+        let mut last: Option<Direction> = None;
+        for _ in 0..moves {
+            let choices: Vec<Direction> = board
+                .get_possible_moves()
+                .into_iter()
+                .filter(|&dir| last != Some(dir.opposite()))
+                .collect();
+            let dir = choices[self.rng.below(choices.len())];
+            board.make_move(dir).unwrap();
+            last = Some(dir);
+        }
+        board
+    }
+
+    fn shuffled_moves(&mut self, board: &Board) -> Vec<Direction> {
+        let mut moves = board.get_possible_moves();
+        self.rng.shuffle(&mut moves);
+        moves
+    }
+
+    /// A puzzle whose optimal solution is exactly `distance` moves long,
+    /// found by walking away from the goal one optimal move at a time in a
+    /// random order, backing up from dead ends. Distances are exact, looked
+    /// up for 3x3 boards and searched with IDA* otherwise, which gets slow
+    /// for long 4x4 distances. `None` if no state is that far from the goal.
+    pub fn at_distance(&mut self, distance: usize) -> Option<Board> {
+        let goal = self.goal();
+        let mut seen: HashSet<Vec<Tile>> = HashSet::from([goal.tiles().to_vec()]);
+        let moves = self.shuffled_moves(&goal);
+        // The board at each distance so far, with its moves left to try
+        let mut path = vec![(goal, moves)];
+
+        while !path.is_empty() {
+            let reached = path.len() - 1;
+            let (board, moves) = path.last_mut().unwrap();
+            if reached == distance {
+                return Some(board.clone());
+            }
+            let Some(dir) = moves.pop() else {
+                path.pop();
+                continue;
+            };
+            let mut next = board.clone();
+            next.make_move(dir).unwrap();
+            // Each state lies at a single distance, so one visit settles it
+            if !seen.insert(next.tiles().to_vec()) {
+                continue;
+            }
+            if self.hinter.distance(&next) == Some(reached + 1) {
+                let moves = self.shuffled_moves(&next);
+                path.push((next, moves));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_same_seed_same_puzzles() {
+        let mut first = PuzzleGenerator::new(4).with_seed(42);
+        let mut second = PuzzleGenerator::new(4).with_seed(42);
+        for _ in 0..5 {
+            assert_eq!(
+                first.random_permutation().get_state(),
+                second.random_permutation().get_state()
+            );
+            assert_eq!(
+                first.random_walk(30).get_state(),
+                second.random_walk(30).get_state()
+            );
+        }
+        let mut other = PuzzleGenerator::new(4).with_seed(43);
+        assert_ne!(
+            PuzzleGenerator::new(4)
+                .with_seed(42)
+                .random_walk(30)
+                .get_state(),
+            other.random_walk(30).get_state()
+        );
+    }
+
+    #[test]
+    fn test_permutations_are_uniform_and_solvable() {
+        // 12 solvable 2x2 states, each expected 1000 times
+        let mut generator = PuzzleGenerator::new(2).with_seed(7);
+        let mut counts: HashMap<Vec<Vec<u16>>, usize> = HashMap::new();
+        for _ in 0..12_000 {
+            let board = generator.random_permutation();
+            assert!(board.is_solvable());
+            *counts.entry(board.get_state()).or_default() += 1;
+        }
+        assert_eq!(counts.len(), 12);
+        assert!(counts.values().all(|&count| (850..1150).contains(&count)));
+    }
+
+    #[test]
+    fn test_walks_never_backtrack() {
+        // On a 2x2 board a walk without backtracking circles the board, so
+        // every 12 moves bring it back to the goal
+        let mut generator = PuzzleGenerator::new(2).with_seed(3);
+        assert!(generator.random_walk(12).is_goal());
+        assert!(!generator.random_walk(6).is_goal());
+    }
+
+    #[test]
+    fn test_exact_distances() {
+        let mut generator = PuzzleGenerator::new(3).with_seed(11);
+        let hinter = Hinter::new();
+        for distance in [0, 1, 5, 20, 31] {
+            let board = generator.at_distance(distance).unwrap();
+            assert_eq!(hinter.distance(&board), Some(distance));
+        }
+        assert!(generator.at_distance(32).is_none());
+    }
+}
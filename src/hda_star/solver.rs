@@ -0,0 +1,394 @@
+use crate::common::{Board, Direction, Heuristic, SearchStats, Tile};
+use crate::{SolutionInfo, Solver};
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering as AtomicOrdering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+// A state handed to the thread that owns it
+struct Message {
+    board: Board,
+    g_cost: u32,
+    parent: Option<(Vec<Tile>, Direction)>,
+}
+
+#[derive(Clone)]
+struct Visit {
+    g_cost: u32,
+    parent: Option<(Vec<Tile>, Direction)>,
+    expanded: bool,
+}
+
+struct OpenEntry {
+    board: Board,
+    g_cost: u32,
+    f_cost: u32,
+}
+
+impl Ord for OpenEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Lower f_cost = higher priority, deeper nodes first among equals
+        other
+            .f_cost
+            .cmp(&self.f_cost)
+            .then_with(|| self.g_cost.cmp(&other.g_cost))
+    }
+}
+
+impl PartialOrd for OpenEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for OpenEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.f_cost == other.f_cost && self.g_cost == other.g_cost
+    }
+}
+
+impl Eq for OpenEntry {}
+
+// State shared by all workers of one search
+struct Shared {
+    // Cost of the best solution found so far
+    incumbent: AtomicU32,
+    goal: Mutex<Option<Vec<Tile>>>,
+    // Busy workers plus messages not yet processed; the search is over at zero
+    work: AtomicUsize,
+}
+
+fn owner(tiles: &[Tile], threads: usize) -> usize {
+    let mut hasher = DefaultHasher::new();
+    tiles.hash(&mut hasher);
+    (hasher.finish() % threads as u64) as usize
+}
+
+struct Worker<'a> {
+    id: usize,
+    heuristic: Heuristic,
+    shared: &'a Shared,
+    outboxes: Vec<Sender<Message>>,
+    open: BinaryHeap<OpenEntry>,
+    visited: HashMap<Vec<Tile>, Visit>,
+    stats: SearchStats,
+}
+
+impl Worker<'_> {
+    fn receive(&mut self, message: Message) {
+        let tiles = message.board.tiles().to_vec();
+        if let Some(visit) = self.visited.get(&tiles) {
+            if visit.g_cost <= message.g_cost {
+                return;
+            }
+        }
+
+        let f_cost = message.g_cost + message.board.heuristic(self.heuristic);
+        if f_cost >= self.shared.incumbent.load(AtomicOrdering::SeqCst) {
+            return;
+        }
+
+        let expanded = self.visited.get(&tiles).is_some_and(|visit| visit.expanded);
+        self.visited.insert(
+            tiles,
+            Visit {
+                g_cost: message.g_cost,
+                parent: message.parent,
+                expanded,
+            },
+        );
+        self.open.push(OpenEntry {
+            board: message.board,
+            g_cost: message.g_cost,
+            f_cost,
+        });
+        self.stats.max_frontier = self.stats.max_frontier.max(self.open.len());
+    }
+
+    fn send(&mut self, message: Message) {
+        let target = owner(message.board.tiles(), self.outboxes.len());
+        if target == self.id {
+            self.receive(message);
+        } else {
+            self.shared.work.fetch_add(1, AtomicOrdering::SeqCst);
+            // Receivers live until the whole search is over
+            self.outboxes[target]
+                .send(message)
+                .expect("worker inbox closed");
+        }
+    }
+
+    // Pops and expands the best local node, if it can still beat the incumbent
+    fn step(&mut self) -> bool {
+        let incumbent = self.shared.incumbent.load(AtomicOrdering::SeqCst);
+        if self
+            .open
+            .peek()
+            .is_none_or(|entry| entry.f_cost >= incumbent)
+        {
+            return false;
+        }
+        let entry = self.open.pop().expect("peeked entry exists");
+
+        let tiles = entry.board.tiles().to_vec();
+        let visit = self
+            .visited
+            .get_mut(&tiles)
+            .expect("open states are visited");
+        if visit.g_cost < entry.g_cost {
+            return true; // Stale entry, a cheaper path was queued since
+        }
+
+        if entry.board.is_goal() {
+            let mut goal = self.shared.goal.lock().unwrap();
+            if entry.g_cost < self.shared.incumbent.load(AtomicOrdering::SeqCst) {
+                self.shared
+                    .incumbent
+                    .store(entry.g_cost, AtomicOrdering::SeqCst);
+                *goal = Some(tiles);
+            }
+            return true;
+        }
+
+        self.stats.nodes_expanded += 1;
+        if visit.expanded {
+            self.stats.re_expansions += 1;
+        }
+        visit.expanded = true;
+        let came_from = visit.parent.as_ref().map(|(_, dir)| *dir);
+
+        for dir in entry.board.get_possible_moves() {
+            if came_from == Some(dir.opposite()) {
+                continue;
+            }
+            let mut board = entry.board.clone();
+            if board.make_move(dir).is_err() {
+                continue;
+            }
+            self.stats.nodes_generated += 1;
+            self.send(Message {
+                board,
+                g_cost: entry.g_cost + 1,
+                parent: Some((tiles.clone(), dir)),
+            });
+        }
+        true
+    }
+
+    fn run(mut self, inbox: Receiver<Message>) -> (HashMap<Vec<Tile>, Visit>, SearchStats) {
+        let work = &self.shared.work;
+        let mut busy = true;
+
+        loop {
+            while let Ok(message) = inbox.try_recv() {
+                if !busy {
+                    work.fetch_add(1, AtomicOrdering::SeqCst);
+                    busy = true;
+                }
+                self.receive(message);
+                work.fetch_sub(1, AtomicOrdering::SeqCst);
+            }
+
+            if self.step() {
+                continue;
+            }
+
+            // Nothing left locally: go idle and wait for more work or global quiescence
+            if busy {
+                busy = false;
+                work.fetch_sub(1, AtomicOrdering::SeqCst);
+            }
+            if work.load(AtomicOrdering::SeqCst) == 0 {
+                break;
+            }
+            match inbox.recv_timeout(Duration::from_micros(200)) {
+                Ok(message) => {
+                    work.fetch_add(1, AtomicOrdering::SeqCst);
+                    busy = true;
+                    self.receive(message);
+                    work.fetch_sub(1, AtomicOrdering::SeqCst);
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+
+        (self.visited, self.stats)
+    }
+}
+
+/// Hash-distributed A* (HDA*): every state is owned by one worker thread,
+/// chosen by hashing its tiles, and successors are sent to their owner.
+pub struct HDAStarSolver {
+    initial_board: Board,
+    heuristic: Heuristic,
+    threads: usize,
+}
+
+impl HDAStarSolver {
+    pub fn with_heuristic(mut self, heuristic: Heuristic) -> Self {
+        self.heuristic = heuristic;
+        self
+    }
+
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    fn default_threads() -> usize {
+        thread::available_parallelism().map_or(1, |n| n.get())
+    }
+
+    /// Solves the puzzle and also returns the statistics of each worker.
+    pub fn solve_with_thread_stats(
+        &self,
+        optimal_length: Option<usize>,
+    ) -> (Option<SolutionInfo>, Vec<SearchStats>) {
+        if !self.initial_board.is_solvable() {
+            return (None, Vec::new());
+        }
+
+        let shared = Shared {
+            incumbent: AtomicU32::new(u32::MAX),
+            goal: Mutex::new(None),
+            work: AtomicUsize::new(self.threads),
+        };
+        let (senders, receivers): (Vec<_>, Vec<_>) =
+            (0..self.threads).map(|_| mpsc::channel()).unzip();
+
+        let root_owner = owner(self.initial_board.tiles(), self.threads);
+        shared.work.fetch_add(1, AtomicOrdering::SeqCst);
+        senders[root_owner]
+            .send(Message {
+                board: self.initial_board.clone(),
+                g_cost: 0,
+                parent: None,
+            })
+            .expect("receiver is alive");
+
+        let results: Vec<(HashMap<Vec<Tile>, Visit>, SearchStats)> = thread::scope(|scope| {
+            let handles: Vec<_> = receivers
+                .into_iter()
+                .enumerate()
+                .map(|(id, inbox)| {
+                    let worker = Worker {
+                        id,
+                        heuristic: self.heuristic,
+                        shared: &shared,
+                        outboxes: senders.clone(),
+                        open: BinaryHeap::new(),
+                        visited: HashMap::new(),
+                        stats: SearchStats::default(),
+                    };
+                    scope.spawn(move || worker.run(inbox))
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("worker panicked"))
+                .collect()
+        });
+
+        let (visited, thread_stats): (Vec<_>, Vec<_>) = results.into_iter().unzip();
+        let goal = shared.goal.into_inner().unwrap();
+        let solution = goal.map(|goal| {
+            // Walk parent links back to the start, asking each state's owner
+            let mut path = Vec::new();
+            let mut tiles = goal;
+            while let Some((parent, dir)) =
+                visited[owner(&tiles, self.threads)][&tiles].parent.clone()
+            {
+                path.push(dir);
+                tiles = parent;
+            }
+            path.reverse();
+
+            let mut stats = SearchStats::default();
+            for worker in &thread_stats {
+                stats.nodes_expanded += worker.nodes_expanded;
+                stats.nodes_generated += worker.nodes_generated;
+                stats.re_expansions += worker.re_expansions;
+                stats.max_frontier += worker.max_frontier;
+            }
+            let cost = self.initial_board.path_cost(&path);
+            let goal = self.initial_board.goal_reached_by(&path);
+            SolutionInfo::new(path, optimal_length)
+                .with_cost(cost)
+                .with_goal(goal)
+                .with_stats(stats)
+        });
+
+        (solution, thread_stats)
+    }
+}
+
+impl Solver for HDAStarSolver {
+    fn new(initial: Board) -> Self {
+        HDAStarSolver {
+            initial_board: initial,
+            heuristic: Heuristic::default(),
+            threads: Self::default_threads(),
+        }
+    }
+
+    fn new_with_goal(initial: Board) -> Self {
+        HDAStarSolver {
+            initial_board: initial,
+            heuristic: Heuristic::default(),
+            threads: Self::default_threads(),
+        }
+    }
+
+    fn solve(&self, optimal_length: Option<usize>) -> Option<SolutionInfo> {
+        self.solve_with_thread_stats(optimal_length).0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AStarSolver;
+
+    #[test]
+    fn test_optimal_for_any_thread_count() {
+        let board = Board::new(vec![vec![8, 6, 7], vec![2, 5, 4], vec![3, 0, 1]]);
+        let astar = AStarSolver::new(board.clone()).solve(None).unwrap();
+
+        for threads in [1, 2, 4] {
+            let (solution, thread_stats) = HDAStarSolver::new(board.clone())
+                .with_threads(threads)
+                .solve_with_thread_stats(None);
+            let solution = solution.unwrap();
+            assert_eq!(solution.moves.len(), astar.moves.len());
+            assert_eq!(thread_stats.len(), threads);
+
+            let mut test_board = board.clone();
+            for dir in solution.moves {
+                test_board.make_move(dir).unwrap();
+            }
+            assert!(test_board.is_goal());
+        }
+    }
+
+    #[test]
+    fn test_already_solved() {
+        let board = Board::new(vec![vec![1, 2], vec![3, 0]]);
+        let solution = HDAStarSolver::new(board).with_threads(3).solve(None);
+        assert!(solution.unwrap().moves.is_empty());
+    }
+
+    #[test]
+    fn test_unsolvable_returns_none() {
+        let board = Board::new(vec![vec![2, 1], vec![3, 0]]);
+        assert!(HDAStarSolver::new(board)
+            .with_threads(2)
+            .solve(None)
+            .is_none());
+    }
+}
@@ -0,0 +1,348 @@
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use crate::common::pattern::MAX_PATTERN_SIZE;
+use crate::ida_star::{BoundedSearch, Outcome};
+use crate::{
+    Board, Direction, DistanceTable, Heuristic, MovePruner, MovePruning, PatternDatabase,
+    SearchLimits,
+};
+
+/// What one candidate move does, in terms of the heuristic and of the true
+/// distance to the goal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MoveAssessment {
+    pub direction: Direction,
+    pub heuristic: Heuristic,
+    pub heuristic_before: u32,
+    pub heuristic_after: u32,
+    /// Optimal moves to the goal after this move, `None` if it is lost
+    pub remaining: Option<usize>,
+    /// Whether the move starts an optimal solution
+    pub optimal: bool,
+}
+
+impl MoveAssessment {
+    /// One line in the style of the BFS trace, e.g.
+    /// "Manhattan: 6 -> 5, 4 moves to go".
+    pub fn reason(&self) -> String {
+        let change = if self.heuristic_after == self.heuristic_before {
+            format!(
+                "{} unchanged: {}",
+                self.heuristic.name(),
+                self.heuristic_before
+            )
+        } else {
+            format!(
+                "{}: {} -> {}",
+                self.heuristic.name(),
+                self.heuristic_before,
+                self.heuristic_after
+            )
+        };
+        match self.remaining {
+            Some(remaining) => format!("{}, {} moves to go", change, remaining),
+            None => format!("{}, goal unreachable", change),
+        }
+    }
+}
+
+/// Finds optimal next moves with the fastest exact method for the board: a
+/// lookup in a `DistanceTable` for 3x3 boards with unit costs, and IDA* with
+/// learned move pruning otherwise. The search is guided by linear conflicts,
+/// and on 4x4 boards also by a `PatternDatabase`. Tables and databases are
+/// kept between calls.
+pub struct Hinter {
+    heuristic: Heuristic,
+    table: Mutex<Option<Arc<DistanceTable>>>,
+    patterns: Mutex<Option<Arc<PatternDatabase>>>,
+}
+
+impl Default for Hinter {
+    fn default() -> Self {
+        Hinter {
+            heuristic: Heuristic::Manhattan,
+            table: Mutex::new(None),
+            patterns: Mutex::new(None),
+        }
+    }
+}
+
+impl Hinter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Heuristic reported by `explain`; it has no say in which move is hinted.
+    pub fn with_heuristic(mut self, heuristic: Heuristic) -> Self {
+        self.heuristic = heuristic;
+        self
+    }
+
+    fn table_for(&self, board: &Board) -> Option<Arc<DistanceTable>> {
+        if board.get_size() != 3 || !board.get_move_cost().is_unit() {
+            return None;
+        }
+        let mut table = self.table.lock().unwrap();
+        match table.as_ref() {
+            Some(table) if table.covers(board) => Some(Arc::clone(table)),
+            _ => {
+                let built = Arc::new(DistanceTable::build(board));
+                *table = Some(Arc::clone(&built));
+                Some(built)
+            }
+        }
+    }
+
+    // Smaller boards have their exact table, larger ones are too big for one
+    fn patterns_for(&self, board: &Board) -> Option<Arc<PatternDatabase>> {
+        if board.get_size() != MAX_PATTERN_SIZE {
+            return None;
+        }
+        let mut patterns = self.patterns.lock().unwrap();
+        match patterns.as_ref() {
+            Some(patterns) if patterns.covers(board) => Some(Arc::clone(patterns)),
+            _ => {
+                let built = Arc::new(PatternDatabase::build(board)?);
+                *patterns = Some(Arc::clone(&built));
+                Some(built)
+            }
+        }
+    }
+
+    // A cheapest path to the goal among those costing from `lower` to below
+    // `upper`, by IDA* starting at the `lower` threshold
+    fn search(&self, board: &Board, lower: u32, upper: u32) -> Option<Vec<Direction>> {
+        let patterns = self.patterns_for(board);
+        let limits = SearchLimits::default();
+        let mut search = BoundedSearch::new(Heuristic::LinearConflict, &limits, Instant::now());
+        search.patterns = patterns.as_deref();
+        search.pruner = MovePruner::for_board(MovePruning::Automaton, board);
+
+        let mut board = board.clone();
+        let mut threshold = lower.max(search.estimate(&board));
+        let mut path = Vec::new();
+        while threshold < upper {
+            match search.search(&mut board, 0, threshold, search.pruner.start(), &mut path) {
+                Outcome::Found => return Some(path),
+                Outcome::Exceeded(next) => threshold = next,
+                Outcome::Aborted => return None,
+            }
+        }
+        None
+    }
+
+    // Iterative deepening never terminates on an unreachable goal
+    fn solve(&self, board: &Board) -> Option<Vec<Direction>> {
+        if !board.is_solvable() {
+            return None;
+        }
+        self.search(board, 0, u32::MAX)
+    }
+
+    /// Optimal number of moves from `board` to the goal.
+    pub fn distance(&self, board: &Board) -> Option<usize> {
+        match self.table_for(board) {
+            Some(table) => table.distance(board).map(usize::from),
+            None => self.solve(board).map(|moves| moves.len()),
+        }
+    }
+
+    /// An optimal next move and the optimal number of moves left before it
+    /// is played, or `None` if the board is solved or cannot be.
+    pub fn hint(&self, board: &Board) -> Option<(Direction, usize)> {
+        if board.is_goal() {
+            return None;
+        }
+        let Some(table) = self.table_for(board) else {
+            let moves = self.solve(board)?;
+            return Some((moves[0], moves.len()));
+        };
+
+        let distance = table.distance(board)?;
+        board.get_possible_moves().into_iter().find_map(|dir| {
+            let mut next = board.clone();
+            next.make_move(dir).ok()?;
+            (table.distance(&next)? + 1 == distance).then_some((dir, distance as usize))
+        })
+    }
+
+    // Moves and cost of a cheapest solution after `dir`, given a cheapest
+    // solution from `board`. Playing `dir` changes the cheapest cost by at
+    // most the price of that tile either way, so only thresholds in that
+    // window are searched, and undoing `dir` covers the top of it.
+    fn after_move(
+        &self,
+        board: &Board,
+        best: &[Direction],
+        dir: Direction,
+        next: &Board,
+    ) -> (usize, u32) {
+        let cost = board.path_cost(best);
+        let price = board.cost_of_move(dir).unwrap();
+        if best.first() == Some(&dir) {
+            return (best.len() - 1, cost - price);
+        }
+        match self.search(next, cost.saturating_sub(price), cost + price) {
+            Some(moves) => (moves.len(), next.path_cost(&moves)),
+            None => (best.len() + 1, cost + price),
+        }
+    }
+
+    /// Every legal move with its effect on the heuristic and on the optimal
+    /// distance. With move costs, a move is optimal when its cost plus the
+    /// cheapest cost after it matches the cheapest cost from `board`.
+    pub fn explain(&self, board: &Board) -> Vec<MoveAssessment> {
+        let heuristic_before = board.heuristic(self.heuristic);
+        let table = self.table_for(board);
+        // Without a table, one search from `board` bounds every other one
+        let best = match table {
+            Some(_) => None,
+            None => self.solve(board),
+        };
+        let mut assessments: Vec<(MoveAssessment, Option<u32>)> = board
+            .get_possible_moves()
+            .into_iter()
+            .filter_map(|dir| {
+                let mut next = board.clone();
+                let cost = next.cost_of_move(dir)?;
+                next.make_move(dir).ok()?;
+                let (remaining, cost_after) = match (&table, &best) {
+                    (Some(table), _) => {
+                        let distance = table.distance(&next).map(usize::from);
+                        (distance, distance.map(|d| d as u32))
+                    }
+                    (None, Some(best)) => {
+                        let (remaining, cost_after) = self.after_move(board, best, dir, &next);
+                        (Some(remaining), Some(cost_after))
+                    }
+                    (None, None) => (None, None),
+                };
+                let assessment = MoveAssessment {
+                    direction: dir,
+                    heuristic: self.heuristic,
+                    heuristic_before,
+                    heuristic_after: next.heuristic(self.heuristic),
+                    remaining,
+                    optimal: false,
+                };
+                Some((assessment, cost_after.map(|after| cost + after)))
+            })
+            .collect();
+
+        let best = assessments.iter().filter_map(|(_, total)| *total).min();
+        for (assessment, total) in &mut assessments {
+            assessment.optimal = best.is_some() && *total == best;
+        }
+        assessments
+            .into_iter()
+            .map(|(assessment, _)| assessment)
+            .collect()
+    }
+}
+
+/// An optimal next move for `board` and the optimal number of moves left,
+/// see `Hinter::hint`. Keep a `Hinter` around to reuse its tables.
+pub fn hint(board: &Board) -> Option<(Direction, usize)> {
+    Hinter::new().hint(board)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hints_follow_an_optimal_path() {
+        let mut board = Board::new(vec![vec![8, 6, 7], vec![2, 5, 4], vec![3, 0, 1]]);
+        let hinter = Hinter::new();
+        let mut expected = 31;
+        while let Some((dir, remaining)) = hinter.hint(&board) {
+            assert_eq!(remaining, expected);
+            board.make_move(dir).unwrap();
+            expected -= 1;
+        }
+        assert_eq!(expected, 0);
+        assert!(board.is_goal());
+    }
+
+    #[test]
+    fn test_larger_boards_use_search() {
+        let board = Board::new(vec![
+            vec![1, 2, 3, 4],
+            vec![5, 6, 0, 8],
+            vec![9, 10, 7, 12],
+            vec![13, 14, 11, 15],
+        ]);
+        assert_eq!(hint(&board), Some((Direction::Down, 3)));
+        assert_eq!(hint(&Board::new(Board::standard_goal(4))), None);
+    }
+
+    #[test]
+    fn test_pattern_database_is_kept_between_calls() {
+        let board = Board::new(vec![
+            vec![1, 2, 3, 4],
+            vec![5, 6, 0, 8],
+            vec![9, 10, 7, 12],
+            vec![13, 14, 11, 15],
+        ]);
+        let hinter = Hinter::new();
+        assert_eq!(hinter.distance(&board), Some(3));
+        let patterns = hinter.patterns_for(&board).unwrap();
+        assert!(patterns.covers(&board));
+        assert!(Arc::ptr_eq(
+            &patterns,
+            &hinter.patterns_for(&board).unwrap()
+        ));
+        assert!(hinter
+            .patterns_for(&Board::new(Board::standard_goal(3)))
+            .is_none());
+    }
+
+    #[test]
+    fn test_bounded_explanation_matches_full_searches() {
+        use crate::{IDAStarSolver, MoveCost, Solver};
+
+        let board = Board::new(vec![vec![4, 1, 3], vec![7, 2, 5], vec![0, 8, 6]])
+            .with_move_cost(MoveCost::FaceValue);
+        let assessments = Hinter::new().explain(&board);
+        let totals: Vec<(usize, u32)> = assessments
+            .iter()
+            .map(|assessment| {
+                let mut next = board.clone();
+                let price = next.cost_of_move(assessment.direction).unwrap();
+                next.make_move(assessment.direction).unwrap();
+                let solution = IDAStarSolver::new(next).solve(None).unwrap();
+                (solution.moves.len(), price + solution.total_cost)
+            })
+            .collect();
+        let best = totals.iter().map(|&(_, total)| total).min();
+
+        for (assessment, (length, total)) in assessments.iter().zip(totals) {
+            assert_eq!(assessment.optimal, Some(total) == best);
+            if assessment.optimal {
+                assert_eq!(assessment.remaining, Some(length));
+            }
+        }
+    }
+
+    #[test]
+    fn test_explanation_marks_optimal_moves() {
+        let board = Board::new(vec![vec![1, 2, 3], vec![4, 0, 6], vec![7, 5, 8]]);
+        let assessments = Hinter::new().explain(&board);
+        assert_eq!(assessments.len(), 4);
+
+        let optimal: Vec<&MoveAssessment> = assessments.iter().filter(|a| a.optimal).collect();
+        assert_eq!(optimal.len(), 1);
+        assert_eq!(optimal[0].direction, Direction::Down);
+        assert_eq!(optimal[0].remaining, Some(1));
+        assert_eq!(
+            (optimal[0].heuristic_before, optimal[0].heuristic_after),
+            (2, 1)
+        );
+        assert_eq!(optimal[0].reason(), "Manhattan: 2 -> 1, 1 moves to go");
+        assert!(assessments
+            .iter()
+            .filter(|a| !a.optimal)
+            .all(|a| a.remaining == Some(3)));
+    }
+}
@@ -0,0 +1,2 @@
+mod advisor;
+pub use advisor::{hint, Hinter, MoveAssessment};
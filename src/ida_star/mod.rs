@@ -0,0 +1,5 @@
+mod parallel;
+mod solver;
+pub use parallel::ParallelIDAStarSolver;
+pub use solver::IDAStarSolver;
+pub(crate) use solver::{BoundedSearch, Outcome};
@@ -0,0 +1,304 @@
+use super::solver::{BoundedSearch, Outcome};
+use crate::common::{
+    Board, Direction, Heuristic, MovePruner, MovePruning, SearchLimits, SearchStats,
+};
+use crate::{SolutionInfo, Solver};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Instant;
+
+// Work units queued per thread, so that uneven subtrees still balance out
+const UNITS_PER_THREAD: usize = 16;
+
+// A shallow node whose subtree is searched by a single worker
+struct WorkUnit {
+    board: Board,
+    g_cost: u32,
+    pruner_state: u32,
+    path: Vec<Direction>,
+}
+
+enum Split {
+    Found(Vec<Direction>),
+    Units(Vec<WorkUnit>, u32),
+}
+
+/// IDA* that splits each iteration's tree at a shallow depth and searches
+/// the resulting subtrees on a pool of worker threads.
+pub struct ParallelIDAStarSolver {
+    initial_board: Board,
+    heuristic: Heuristic,
+    pruning: MovePruning,
+    limits: SearchLimits,
+    threads: usize,
+}
+
+impl ParallelIDAStarSolver {
+    pub fn with_heuristic(mut self, heuristic: Heuristic) -> Self {
+        self.heuristic = heuristic;
+        self
+    }
+
+    pub fn with_pruning(mut self, pruning: MovePruning) -> Self {
+        self.pruning = pruning;
+        self
+    }
+
+    pub fn with_limits(mut self, limits: SearchLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    fn default_threads() -> usize {
+        thread::available_parallelism().map_or(1, |n| n.get())
+    }
+
+    fn solution(
+        &self,
+        path: Vec<Direction>,
+        optimal_length: Option<usize>,
+        stats: SearchStats,
+    ) -> SolutionInfo {
+        let cost = self.initial_board.path_cost(&path);
+        let goal = self.initial_board.goal_reached_by(&path);
+        SolutionInfo::new(path, optimal_length)
+            .with_cost(cost)
+            .with_goal(goal)
+            .with_stats(stats)
+    }
+
+    // Expands the tree breadth-first until there are enough subtrees to hand out
+    fn split(&self, threshold: u32, pruner: &MovePruner, stats: &mut SearchStats) -> Split {
+        let target = self.threads * UNITS_PER_THREAD;
+        let mut next_threshold = u32::MAX;
+        let mut frontier = vec![WorkUnit {
+            board: self.initial_board.clone(),
+            g_cost: 0,
+            pruner_state: pruner.start(),
+            path: Vec::new(),
+        }];
+
+        while frontier.len() < target && !frontier.is_empty() {
+            let mut next = Vec::new();
+            for unit in frontier {
+                if unit.board.is_goal() {
+                    return Split::Found(unit.path);
+                }
+                stats.nodes_expanded += 1;
+
+                for dir in unit.board.get_possible_moves() {
+                    let Some(pruner_state) = pruner.step(unit.pruner_state, dir) else {
+                        stats.pruned_moves += 1;
+                        continue;
+                    };
+                    let Some(move_cost) = unit.board.cost_of_move(dir) else {
+                        continue;
+                    };
+                    let mut board = unit.board.clone();
+                    board.make_move(dir).unwrap();
+                    stats.nodes_generated += 1;
+
+                    let g_cost = unit.g_cost + move_cost;
+                    let f_cost = g_cost + board.heuristic(self.heuristic);
+                    if f_cost > threshold {
+                        next_threshold = next_threshold.min(f_cost);
+                        continue;
+                    }
+                    let mut path = unit.path.clone();
+                    path.push(dir);
+                    next.push(WorkUnit {
+                        board,
+                        g_cost,
+                        pruner_state,
+                        path,
+                    });
+                }
+            }
+            frontier = next;
+        }
+
+        Split::Units(frontier, next_threshold)
+    }
+
+    // One iteration: every worker pulls units until they run out or someone succeeds
+    fn run_iteration(
+        &self,
+        units: Vec<WorkUnit>,
+        threshold: u32,
+        pruner: &MovePruner,
+        started: Instant,
+        shared_nodes: &AtomicUsize,
+        stats: &mut SearchStats,
+    ) -> (Option<Vec<Direction>>, u32, bool) {
+        let next_unit = AtomicUsize::new(0);
+        let next_threshold = AtomicU32::new(u32::MAX);
+        let stop = AtomicBool::new(false);
+        let aborted = AtomicBool::new(false);
+        let solution = Mutex::new(None);
+        let units: Vec<Mutex<Option<WorkUnit>>> = units
+            .into_iter()
+            .map(|unit| Mutex::new(Some(unit)))
+            .collect();
+
+        let worker_stats: Vec<SearchStats> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..self.threads)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut search = BoundedSearch::new(self.heuristic, &self.limits, started);
+                        search.pruner = *pruner;
+                        search.stop = Some(&stop);
+                        search.shared_nodes = Some(shared_nodes);
+
+                        while !stop.load(Ordering::Relaxed) {
+                            let index = next_unit.fetch_add(1, Ordering::Relaxed);
+                            let Some(slot) = units.get(index) else {
+                                break;
+                            };
+                            let mut unit = slot.lock().unwrap().take().expect("unit taken once");
+                            match search.search(
+                                &mut unit.board,
+                                unit.g_cost,
+                                threshold,
+                                unit.pruner_state,
+                                &mut unit.path,
+                            ) {
+                                Outcome::Found => {
+                                    stop.store(true, Ordering::Relaxed);
+                                    solution.lock().unwrap().get_or_insert(unit.path);
+                                }
+                                Outcome::Exceeded(f_cost) => {
+                                    next_threshold.fetch_min(f_cost, Ordering::Relaxed);
+                                }
+                                Outcome::Aborted => {
+                                    // Another worker finished first, or a limit was hit
+                                    if solution.lock().unwrap().is_none() {
+                                        aborted.store(true, Ordering::Relaxed);
+                                        stop.store(true, Ordering::Relaxed);
+                                    }
+                                }
+                            }
+                        }
+                        search.stats
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("worker panicked"))
+                .collect()
+        });
+
+        for worker in worker_stats {
+            stats.nodes_expanded += worker.nodes_expanded;
+            stats.nodes_generated += worker.nodes_generated;
+            stats.pruned_moves += worker.pruned_moves;
+        }
+        let solution = solution.into_inner().unwrap();
+        let aborted = solution.is_none() && aborted.load(Ordering::Relaxed);
+        (solution, next_threshold.into_inner(), aborted)
+    }
+}
+
+impl Solver for ParallelIDAStarSolver {
+    fn new(initial: Board) -> Self {
+        ParallelIDAStarSolver {
+            initial_board: initial,
+            heuristic: Heuristic::default(),
+            pruning: MovePruning::default(),
+            limits: SearchLimits::default(),
+            threads: Self::default_threads(),
+        }
+    }
+
+    fn new_with_goal(initial: Board) -> Self {
+        ParallelIDAStarSolver {
+            initial_board: initial,
+            heuristic: Heuristic::default(),
+            pruning: MovePruning::default(),
+            limits: SearchLimits::default(),
+            threads: Self::default_threads(),
+        }
+    }
+
+    fn solve(&self, optimal_length: Option<usize>) -> Option<SolutionInfo> {
+        if !self.initial_board.is_solvable() {
+            return None;
+        }
+
+        let started = Instant::now();
+        let shared_nodes = AtomicUsize::new(0);
+        let mut stats = SearchStats::default();
+        let mut threshold = self.initial_board.heuristic(self.heuristic);
+        let pruner = MovePruner::for_board(self.pruning, &self.initial_board);
+
+        loop {
+            stats.iterations += 1;
+            let (units, split_threshold) = match self.split(threshold, &pruner, &mut stats) {
+                Split::Found(path) => return Some(self.solution(path, optimal_length, stats)),
+                Split::Units(units, next) => (units, next),
+            };
+            stats.max_frontier = stats.max_frontier.max(units.len());
+
+            let (solution, next, aborted) = self.run_iteration(
+                units,
+                threshold,
+                &pruner,
+                started,
+                &shared_nodes,
+                &mut stats,
+            );
+            if let Some(path) = solution {
+                return Some(self.solution(path, optimal_length, stats));
+            }
+            if aborted {
+                return None;
+            }
+
+            threshold = next.min(split_threshold);
+            if threshold == u32::MAX {
+                return None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AStarSolver;
+
+    #[test]
+    fn test_matches_astar_length() {
+        let board = Board::new(vec![vec![8, 6, 7], vec![2, 5, 4], vec![3, 0, 1]]);
+        let astar = AStarSolver::new(board.clone()).solve(None).unwrap();
+
+        for threads in [1, 3] {
+            let solution = ParallelIDAStarSolver::new(board.clone())
+                .with_threads(threads)
+                .solve(None)
+                .unwrap();
+            assert_eq!(solution.moves.len(), astar.moves.len());
+
+            let mut test_board = board.clone();
+            for dir in solution.moves {
+                test_board.make_move(dir).unwrap();
+            }
+            assert!(test_board.is_goal());
+        }
+    }
+
+    #[test]
+    fn test_goal_found_while_splitting() {
+        let board = Board::new(vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 0, 8]]);
+        let solution = ParallelIDAStarSolver::new(board)
+            .with_threads(2)
+            .solve(None);
+        assert_eq!(solution.unwrap().moves, vec![Direction::Right]);
+    }
+}
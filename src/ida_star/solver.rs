@@ -0,0 +1,288 @@
+use crate::common::{
+    Board, Direction, Heuristic, MovePruner, MovePruning, PatternDatabase, SearchLimits,
+    SearchStats,
+};
+use crate::{SolutionInfo, Solver};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Instant;
+
+// How many expansions a worker counts locally before publishing them
+const NODE_BATCH: usize = 1024;
+
+pub(crate) enum Outcome {
+    Found,
+    Exceeded(u32),
+    Aborted,
+}
+
+// Threshold-bounded depth-first search shared by the sequential and parallel solvers
+pub(crate) struct BoundedSearch<'a> {
+    pub heuristic: Heuristic,
+    // Tightens the heuristic when set; it must cover the searched board
+    pub patterns: Option<&'a PatternDatabase>,
+    pub pruner: MovePruner,
+    pub limits: &'a SearchLimits,
+    pub started: Instant,
+    pub stats: SearchStats,
+    // Set by another worker once the search is over
+    pub stop: Option<&'a AtomicBool>,
+    // Expansions of all workers, for the node limit
+    pub shared_nodes: Option<&'a AtomicUsize>,
+    unpublished: usize,
+}
+
+impl<'a> BoundedSearch<'a> {
+    pub fn new(heuristic: Heuristic, limits: &'a SearchLimits, started: Instant) -> Self {
+        BoundedSearch {
+            heuristic,
+            patterns: None,
+            pruner: MovePruner::default(),
+            limits,
+            started,
+            stats: SearchStats::default(),
+            stop: None,
+            shared_nodes: None,
+            unpublished: 0,
+        }
+    }
+
+    pub fn estimate(&self, board: &Board) -> u32 {
+        let estimate = board.heuristic(self.heuristic);
+        match self.patterns {
+            Some(patterns) => estimate.max(patterns.evaluate(board)),
+            None => estimate,
+        }
+    }
+
+    fn should_stop(&mut self) -> bool {
+        let nodes = match self.shared_nodes {
+            Some(shared) => {
+                self.unpublished += 1;
+                if self.unpublished < NODE_BATCH {
+                    return self.stop.is_some_and(|stop| stop.load(Ordering::Relaxed));
+                }
+                let published = self.unpublished;
+                self.unpublished = 0;
+                shared.fetch_add(published, Ordering::Relaxed) + published
+            }
+            None => self.stats.nodes_expanded,
+        };
+        self.stop.is_some_and(|stop| stop.load(Ordering::Relaxed))
+            || self.limits.exceeded(nodes, self.started)
+    }
+
+    /// Explores every path through `board` whose f-cost stays within `threshold`,
+    /// leaving the moves to the goal in `path` when one is found. `pruner_state`
+    /// is where the move pruner stands after the moves leading to `board`.
+    pub fn search(
+        &mut self,
+        board: &mut Board,
+        g_cost: u32,
+        threshold: u32,
+        pruner_state: u32,
+        path: &mut Vec<Direction>,
+    ) -> Outcome {
+        let f_cost = g_cost + self.estimate(board);
+        if f_cost > threshold {
+            return Outcome::Exceeded(f_cost);
+        }
+        if board.is_goal() {
+            return Outcome::Found;
+        }
+
+        self.stats.nodes_expanded += 1;
+        if self.should_stop() {
+            return Outcome::Aborted;
+        }
+
+        let mut next_threshold = u32::MAX;
+        for dir in board.get_possible_moves() {
+            let Some(next_state) = self.pruner.step(pruner_state, dir) else {
+                self.stats.pruned_moves += 1;
+                continue;
+            };
+            let Some(move_cost) = board.cost_of_move(dir) else {
+                continue;
+            };
+            board.make_move(dir).unwrap();
+            self.stats.nodes_generated += 1;
+            path.push(dir);
+
+            let outcome = self.search(board, g_cost + move_cost, threshold, next_state, path);
+            match outcome {
+                Outcome::Found => return Outcome::Found,
+                Outcome::Aborted => {
+                    path.pop();
+                    board.make_move(dir.opposite()).unwrap();
+                    return Outcome::Aborted;
+                }
+                Outcome::Exceeded(f_cost) => next_threshold = next_threshold.min(f_cost),
+            }
+
+            path.pop();
+            board.make_move(dir.opposite()).unwrap();
+        }
+
+        Outcome::Exceeded(next_threshold)
+    }
+}
+
+pub struct IDAStarSolver {
+    initial_board: Board,
+    heuristic: Heuristic,
+    pruning: MovePruning,
+    limits: SearchLimits,
+}
+
+impl IDAStarSolver {
+    pub fn with_heuristic(mut self, heuristic: Heuristic) -> Self {
+        self.heuristic = heuristic;
+        self
+    }
+
+    pub fn with_pruning(mut self, pruning: MovePruning) -> Self {
+        self.pruning = pruning;
+        self
+    }
+
+    pub fn with_limits(mut self, limits: SearchLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+}
+
+impl Solver for IDAStarSolver {
+    fn new(initial: Board) -> Self {
+        IDAStarSolver {
+            initial_board: initial,
+            heuristic: Heuristic::default(),
+            pruning: MovePruning::default(),
+            limits: SearchLimits::default(),
+        }
+    }
+
+    fn new_with_goal(initial: Board) -> Self {
+        IDAStarSolver {
+            initial_board: initial,
+            heuristic: Heuristic::default(),
+            pruning: MovePruning::default(),
+            limits: SearchLimits::default(),
+        }
+    }
+
+    fn solve(&self, optimal_length: Option<usize>) -> Option<SolutionInfo> {
+        // Iterative deepening never terminates on an unreachable goal
+        if !self.initial_board.is_solvable() {
+            return None;
+        }
+
+        let mut search = BoundedSearch::new(self.heuristic, &self.limits, Instant::now());
+        search.pruner = MovePruner::for_board(self.pruning, &self.initial_board);
+        let mut board = self.initial_board.clone();
+        let mut threshold = board.heuristic(self.heuristic);
+        let mut path = Vec::new();
+
+        loop {
+            search.stats.iterations += 1;
+            match search.search(&mut board, 0, threshold, search.pruner.start(), &mut path) {
+                Outcome::Found => {
+                    let cost = self.initial_board.path_cost(&path);
+                    let goal = self.initial_board.goal_reached_by(&path);
+                    return Some(
+                        SolutionInfo::new(path, optimal_length)
+                            .with_cost(cost)
+                            .with_goal(goal)
+                            .with_stats(search.stats),
+                    );
+                }
+                Outcome::Exceeded(u32::MAX) | Outcome::Aborted => return None,
+                Outcome::Exceeded(next) => threshold = next,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AStarSolver;
+
+    #[test]
+    fn test_matches_astar_length() {
+        let board = Board::new(vec![vec![8, 6, 7], vec![2, 5, 4], vec![3, 0, 1]]);
+        let astar = AStarSolver::new(board.clone()).solve(None).unwrap();
+        let solution = IDAStarSolver::new(board.clone())
+            .with_heuristic(Heuristic::LinearConflict)
+            .solve(None)
+            .unwrap();
+
+        assert_eq!(solution.moves.len(), astar.moves.len());
+        assert!(solution.stats.iterations > 1);
+
+        let mut test_board = board;
+        for dir in solution.moves {
+            test_board.make_move(dir).unwrap();
+        }
+        assert!(test_board.is_goal());
+    }
+
+    #[test]
+    fn test_node_limit_gives_up() {
+        let board = Board::new(vec![vec![8, 6, 7], vec![2, 5, 4], vec![3, 0, 1]]);
+        let solver =
+            IDAStarSolver::new(board).with_limits(SearchLimits::default().with_max_nodes(100));
+        assert!(solver.solve(None).is_none());
+    }
+
+    #[test]
+    fn test_blocked_cell_is_never_entered() {
+        let mask = (0..4)
+            .map(|row| (0..4).map(|col| (row, col) == (1, 1)).collect())
+            .collect();
+        let board = Board::new(vec![
+            vec![5, 1, 2, 3],
+            vec![9, 6, 7, 4],
+            vec![0, 10, 11, 8],
+            vec![13, 14, 15, 12],
+        ])
+        .with_blocked_cells(mask);
+
+        let astar = AStarSolver::new(board.clone()).solve(None).unwrap();
+        let solution = IDAStarSolver::new(board.clone()).solve(None).unwrap();
+        assert_eq!(solution.moves.len(), astar.moves.len());
+
+        let mut test_board = board;
+        for dir in solution.moves {
+            test_board.make_move(dir).unwrap();
+            assert_eq!(test_board.tiles()[5], 6);
+        }
+        assert!(test_board.is_goal());
+    }
+
+    #[test]
+    fn test_automaton_prunes_more_than_inverse_moves() {
+        let board = Board::new(vec![vec![8, 6, 7], vec![2, 5, 4], vec![3, 0, 1]]);
+        let solve = |pruning| {
+            IDAStarSolver::new(board.clone())
+                .with_pruning(pruning)
+                .solve(None)
+                .unwrap()
+        };
+        let unpruned = solve(MovePruning::None);
+        let inverse = solve(MovePruning::Inverse);
+        let automaton = solve(MovePruning::Automaton);
+
+        assert_eq!(unpruned.stats.pruned_moves, 0);
+        assert!(inverse.stats.pruned_moves > 0);
+        assert_eq!(inverse.moves.len(), unpruned.moves.len());
+        assert_eq!(automaton.moves.len(), unpruned.moves.len());
+        assert!(inverse.stats.nodes_expanded < unpruned.stats.nodes_expanded);
+        assert!(automaton.stats.nodes_expanded < inverse.stats.nodes_expanded);
+
+        let mut test_board = board;
+        for dir in automaton.moves {
+            test_board.make_move(dir).unwrap();
+        }
+        assert!(test_board.is_goal());
+    }
+}
@@ -1,24 +1,97 @@
 pub mod a_star;
+pub mod batch;
+pub mod bench;
 pub mod bfs;
 pub mod common;
 pub mod dfs;
+pub mod enumeration;
+pub mod fringe;
+pub mod generator;
+pub mod hda_star;
+pub mod hint;
+pub mod ida_star;
+pub mod lrta_star;
+pub mod play;
+pub mod rating;
+pub mod rbfs;
+pub mod reduction;
+pub mod registry;
+pub mod shorten;
+pub mod sma_star;
+pub mod table;
+pub mod ucs;
+pub mod yen;
 
 // Re-export common types that other modules will use
-pub use common::{Board, ColoredText, Direction};
+pub use common::verify::verify;
+pub use common::{
+    Board, ColoredText, Direction, Heuristic, MoveCost, MovePruner, MovePruning, PatternDatabase,
+    SearchLimits, SearchStats, Tile, VerifyError, WILDCARD,
+};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SolutionQuality {
     Optimal,
     Good,
+    Fair,
     Poor,
+    /// No optimum to compare with
+    Unknown,
+}
+
+/// Largest ratio of a solution's length (or cost) to the optimal one that
+/// still earns each grade; anything above what `fair` allows is poor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QualityThresholds {
+    pub good: f64,
+    pub fair: f64,
+}
+
+impl Default for QualityThresholds {
+    fn default() -> Self {
+        QualityThresholds {
+            good: 1.2,
+            fair: 1.5,
+        }
+    }
 }
 
 impl SolutionQuality {
+    /// Grades a solution's length, or cost, against the optimal one.
+    pub fn grade(value: usize, optimum: Option<usize>, thresholds: &QualityThresholds) -> Self {
+        let Some(optimum) = optimum else {
+            return SolutionQuality::Unknown;
+        };
+        if value <= optimum {
+            return SolutionQuality::Optimal;
+        }
+        let ratio = value as f64 / optimum.max(1) as f64;
+        if ratio <= thresholds.good {
+            SolutionQuality::Good
+        } else if ratio <= thresholds.fair {
+            SolutionQuality::Fair
+        } else {
+            SolutionQuality::Poor
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SolutionQuality::Optimal => "Optimal",
+            SolutionQuality::Good => "Good",
+            SolutionQuality::Fair => "Fair",
+            SolutionQuality::Poor => "Poor",
+            SolutionQuality::Unknown => "Ungraded",
+        }
+    }
+
     pub fn to_colored_string(&self) -> String {
         match self {
-            SolutionQuality::Optimal => ColoredText::green("Optimal"),
-            SolutionQuality::Good => ColoredText::yellow("Good"),
-            SolutionQuality::Poor => ColoredText::red("Poor"),
+            SolutionQuality::Optimal => ColoredText::green(self.name()),
+            SolutionQuality::Good => ColoredText::cyan(self.name()),
+            SolutionQuality::Fair => ColoredText::yellow(self.name()),
+            SolutionQuality::Poor => ColoredText::red(self.name()),
+            SolutionQuality::Unknown => self.name().to_string(),
         }
     }
 }
@@ -27,36 +100,90 @@ impl SolutionQuality {
 pub struct SolutionInfo {
     pub moves: Vec<Direction>,
     pub optimal_length: Option<usize>,
+    /// Cheapest cost to the goal, when the solution was graded by cost
+    pub optimal_cost: Option<u32>,
     pub quality: SolutionQuality,
+    /// Sum of the move costs along the path; equals the move count for unit costs
+    pub total_cost: u32,
+    /// Index into `Board::goal_states` of the goal the moves lead to
+    pub reached_goal: Option<usize>,
+    pub stats: SearchStats,
 }
 
 impl SolutionInfo {
+    /// Graded with the default thresholds; see `with_optimal_length`.
     pub fn new(moves: Vec<Direction>, optimal_length: Option<usize>) -> Self {
-        let quality = if let Some(optimal) = optimal_length {
-            match moves.len() {
-                len if len == optimal => SolutionQuality::Optimal,
-                len if len <= optimal + 5 => SolutionQuality::Good,
-                _ => SolutionQuality::Poor,
-            }
-        } else {
-            SolutionQuality::Good // Default when optimal length is unknown
-        };
-
+        let quality =
+            SolutionQuality::grade(moves.len(), optimal_length, &QualityThresholds::default());
+        let total_cost = moves.len() as u32;
         SolutionInfo {
             moves,
             optimal_length,
+            optimal_cost: None,
             quality,
+            total_cost,
+            reached_goal: None,
+            stats: SearchStats::default(),
         }
     }
 
+    /// Grades the solution against `optimal_length`, found after the fact.
+    pub fn with_optimal_length(
+        mut self,
+        optimal_length: Option<usize>,
+        thresholds: &QualityThresholds,
+    ) -> Self {
+        self.optimal_length = optimal_length;
+        self.quality = SolutionQuality::grade(self.moves.len(), optimal_length, thresholds);
+        self
+    }
+
+    /// Grades the solution by its cost against `optimal_cost`, for boards
+    /// whose moves have different prices. The fewest moves would rate a
+    /// longer but cheaper path below a shorter, more expensive one.
+    pub fn with_optimal_cost(
+        mut self,
+        optimal_cost: Option<u32>,
+        thresholds: &QualityThresholds,
+    ) -> Self {
+        self.optimal_cost = optimal_cost;
+        self.quality = SolutionQuality::grade(
+            self.total_cost as usize,
+            optimal_cost.map(|cost| cost as usize),
+            thresholds,
+        );
+        self
+    }
+
+    pub fn with_cost(mut self, total_cost: u32) -> Self {
+        self.total_cost = total_cost;
+        self
+    }
+
+    pub fn with_goal(mut self, reached_goal: Option<usize>) -> Self {
+        self.reached_goal = reached_goal;
+        self
+    }
+
+    pub fn with_stats(mut self, stats: SearchStats) -> Self {
+        self.stats = stats;
+        self
+    }
+
     pub fn display_solution(&self) -> String {
         let quality_str = self.quality.to_colored_string();
         let moves_len = self.moves.len();
 
         let mut result = format!("Found {} solution in {} moves", quality_str, moves_len);
+        if self.total_cost != moves_len as u32 {
+            result.push_str(&format!(" (cost {})", self.total_cost));
+        }
         if let Some(optimal) = self.optimal_length {
             result.push_str(&format!(" (Optimal: {})", optimal));
         }
+        if let Some(optimal) = self.optimal_cost {
+            result.push_str(&format!(" (Cheapest cost: {})", optimal));
+        }
         result
     }
 }
@@ -69,5 +196,59 @@ pub trait Solver {
 
 // Re-export solvers
 pub use a_star::AStarSolver;
+pub use batch::{BatchRecord, BatchSolver};
+pub use bench::{BenchRow, Benchmark, Instance, InstanceSet};
 pub use bfs::BFSSolver;
 pub use dfs::DFSSolver;
+pub use enumeration::{SolutionEnumerator, SolutionSet};
+pub use fringe::FringeSolver;
+pub use generator::PuzzleGenerator;
+pub use hda_star::HDAStarSolver;
+pub use hint::{hint, Hinter, MoveAssessment};
+pub use ida_star::{IDAStarSolver, ParallelIDAStarSolver};
+pub use lrta_star::{LRTAStarAgent, TrialReport};
+pub use play::{Game, PlayOutcome};
+pub use rating::{rate, Difficulty, Rating};
+pub use rbfs::RBFSSolver;
+pub use reduction::ReductionSolver;
+pub use registry::{SolverKind, SolverOptions};
+pub use shorten::{PathShortener, ShorteningReport};
+pub use sma_star::SMAStarSolver;
+pub use table::{DistanceTable, TableSolver};
+pub use ucs::UniformCostSolver;
+pub use yen::YenSolver;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grades_follow_ratio_thresholds() {
+        let thresholds = QualityThresholds {
+            good: 1.1,
+            fair: 2.0,
+        };
+        let grade = |length| SolutionQuality::grade(length, Some(20), &thresholds);
+        assert_eq!(grade(20), SolutionQuality::Optimal);
+        assert_eq!(grade(22), SolutionQuality::Good);
+        assert_eq!(grade(40), SolutionQuality::Fair);
+        assert_eq!(grade(42), SolutionQuality::Poor);
+
+        let solution = SolutionInfo::new(vec![Direction::Up; 6], None);
+        assert_eq!(solution.quality, SolutionQuality::Unknown);
+        let solution = solution.with_optimal_length(Some(2), &QualityThresholds::default());
+        assert_eq!(solution.quality, SolutionQuality::Poor);
+    }
+
+    #[test]
+    fn test_cost_grading_ignores_length() {
+        // Six cheap moves against a cheapest cost of 6, where 2 moves suffice
+        let solution = SolutionInfo::new(vec![Direction::Up; 6], None)
+            .with_cost(6)
+            .with_optimal_cost(Some(6), &QualityThresholds::default());
+        assert_eq!(solution.quality, SolutionQuality::Optimal);
+        let solution = solution.with_optimal_cost(Some(5), &QualityThresholds::default());
+        assert_eq!(solution.quality, SolutionQuality::Good);
+        assert_eq!(solution.optimal_cost, Some(5));
+    }
+}
@@ -0,0 +1,184 @@
+use crate::common::{Board, Direction, Heuristic, Tile};
+use std::collections::HashMap;
+
+/// Outcome of running the agent repeatedly on the same board.
+#[derive(Clone, Debug, Default)]
+pub struct TrialReport {
+    /// Moves taken in each trial, `None` when the step budget ran out
+    pub lengths: Vec<Option<usize>>,
+    /// Heuristic values raised during each trial
+    pub updates: Vec<usize>,
+    /// First trial that neither learned anything nor changed length
+    pub converged_after: Option<usize>,
+}
+
+/// Learning real-time A* (LRTA*): commits to one move at a time after a
+/// bounded lookahead, and raises the heuristic of the states it leaves so
+/// that later trials on the same board take shorter paths.
+pub struct LRTAStarAgent {
+    heuristic: Heuristic,
+    lookahead: usize,
+    learned: HashMap<Vec<Tile>, u32>,
+    updates: usize,
+}
+
+impl LRTAStarAgent {
+    pub fn new(heuristic: Heuristic) -> Self {
+        LRTAStarAgent {
+            heuristic,
+            lookahead: 1,
+            learned: HashMap::new(),
+            updates: 0,
+        }
+    }
+
+    pub fn with_lookahead(mut self, depth: usize) -> Self {
+        self.lookahead = depth.max(1);
+        self
+    }
+
+    /// Current (possibly learned) estimate of the distance to the goal.
+    pub fn estimate(&self, board: &Board) -> u32 {
+        self.learned
+            .get(board.tiles())
+            .copied()
+            .unwrap_or_else(|| board.heuristic(self.heuristic))
+    }
+
+    pub fn learned_states(&self) -> usize {
+        self.learned.len()
+    }
+
+    // Cheapest cost-to-goal estimate seen from `board` within `depth` more moves
+    fn lookahead_value(&self, board: &mut Board, depth: usize, last_move: Direction) -> u32 {
+        if board.is_goal() {
+            return 0;
+        }
+        if depth == 0 {
+            return self.estimate(board);
+        }
+
+        let mut best = u32::MAX;
+        for dir in board.get_possible_moves() {
+            if dir == last_move.opposite() || board.make_move(dir).is_err() {
+                continue;
+            }
+            let value = self
+                .lookahead_value(board, depth - 1, dir)
+                .saturating_add(1);
+            best = best.min(value);
+            board.make_move(dir.opposite()).unwrap();
+        }
+        // Learned values of interior states are lower bounds too, and keep the
+        // agent from circling through states it has already learned about
+        best.max(self.estimate(board))
+    }
+
+    /// Picks the next move from `board` and updates the heuristic of `board`.
+    /// Returns `None` once the goal is reached.
+    pub fn next_move(&mut self, board: &Board) -> Option<Direction> {
+        if board.is_goal() {
+            return None;
+        }
+
+        let mut best: Option<(u32, Direction)> = None;
+        let mut scratch = board.clone();
+        for dir in board.get_possible_moves() {
+            if scratch.make_move(dir).is_err() {
+                continue;
+            }
+            let value = self
+                .lookahead_value(&mut scratch, self.lookahead - 1, dir)
+                .saturating_add(1);
+            scratch.make_move(dir.opposite()).unwrap();
+
+            if best.is_none_or(|(best_value, _)| value < best_value) {
+                best = Some((value, dir));
+            }
+        }
+
+        let (value, dir) = best?;
+        // Never lower an estimate: the max keeps the table admissible and monotone
+        if value > self.estimate(board) {
+            self.learned.insert(board.tiles().to_vec(), value);
+            self.updates += 1;
+        }
+        Some(dir)
+    }
+
+    /// Walks from `board` to the goal, learning along the way.
+    pub fn run_trial(&mut self, board: &Board, max_steps: usize) -> Option<Vec<Direction>> {
+        let mut current = board.clone();
+        let mut moves = Vec::new();
+
+        while moves.len() < max_steps {
+            match self.next_move(&current) {
+                Some(dir) => {
+                    current.make_move(dir).unwrap();
+                    moves.push(dir);
+                }
+                None => return Some(moves),
+            }
+        }
+        current.is_goal().then_some(moves)
+    }
+
+    /// Repeats trials from the same board, keeping what was learned in between.
+    pub fn run_trials(&mut self, board: &Board, trials: usize, max_steps: usize) -> TrialReport {
+        let mut report = TrialReport::default();
+
+        for trial in 0..trials {
+            let updates_before = self.updates;
+            let length = self.run_trial(board, max_steps).map(|moves| moves.len());
+            let updates = self.updates - updates_before;
+
+            let unchanged = report.lengths.last() == Some(&length);
+            report.lengths.push(length);
+            report.updates.push(updates);
+
+            if updates == 0 && unchanged && length.is_some() {
+                report.converged_after = Some(trial);
+                break;
+            }
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AStarSolver, Solver};
+
+    #[test]
+    fn test_step_api_reaches_goal() {
+        let mut board = Board::new(vec![vec![1, 2, 3], vec![4, 0, 6], vec![7, 5, 8]]);
+        let mut agent = LRTAStarAgent::new(Heuristic::Manhattan);
+
+        let mut steps = 0;
+        while let Some(dir) = agent.next_move(&board) {
+            board.make_move(dir).unwrap();
+            steps += 1;
+            assert!(steps < 1000, "agent should reach the goal");
+        }
+        assert!(board.is_goal());
+    }
+
+    #[test]
+    fn test_trials_converge_to_optimal() {
+        let board = Board::new(vec![vec![4, 1, 2], vec![0, 8, 7], vec![6, 3, 5]]);
+        let optimal = AStarSolver::new(board.clone())
+            .solve(None)
+            .unwrap()
+            .moves
+            .len();
+
+        let mut agent = LRTAStarAgent::new(Heuristic::Manhattan).with_lookahead(2);
+        let report = agent.run_trials(&board, 500, 10_000);
+
+        let converged = report.converged_after.expect("should converge");
+        assert_eq!(report.lengths[converged], Some(optimal));
+        assert!(report.lengths[0] > Some(optimal));
+        assert!(agent.learned_states() > 0);
+    }
+}
@@ -0,0 +1,2 @@
+mod agent;
+pub use agent::{LRTAStarAgent, TrialReport};
@@ -1,104 +1,1076 @@
-use serde_json::Value;
+use colored::*;
+use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::fs;
-use std::io::{self, Write};
+use std::process::ExitCode;
+use std::str::FromStr;
 use std::thread;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use taquin::common::{print_side_by_side, write_results_to_file, Config, MethodState};
-use taquin::{AStarSolver, BFSSolver, Board, DFSSolver, Solver};
+use taquin::common::input::{
+    board_to_json, format_moves, load_board, parse_moves, read_json, read_puzzles, read_solutions,
+    solution_to_json, SolutionEntry,
+};
+use taquin::common::{print_side_by_side, write_results_to_file, MethodState};
+use taquin::play::play;
+use taquin::{
+    rate, verify, BatchSolver, BenchRow, Benchmark, Board, DistanceTable, Game, Heuristic, Hinter,
+    IDAStarSolver, Instance, InstanceSet, MovePruning, PathShortener, PlayOutcome, PuzzleGenerator,
+    QualityThresholds, Rating, SearchLimits, ShorteningReport, SolutionInfo, Solver, SolverKind,
+    SolverOptions,
+};
 
-fn load_board_from_file(path: &str) -> Board {
-    let content = fs::read_to_string(path).expect("Failed to read config file");
-    let json: Value = serde_json::from_str(&content).expect("Failed to parse JSON");
-    let board_state: Vec<Vec<i32>> = json
-        .get("board")
-        .expect("Missing board field")
-        .as_array()
-        .expect("Invalid board format")
+const USAGE: &str = "\
+Usage: taquin <command> [options]
+
+Commands:
+  solve      Solve a puzzle with one or more solvers
+  generate   Write random solvable puzzles as JSON
+  verify     Check that a move sequence solves a puzzle
+  hint       Suggest an optimal next move
+  rate       Rate how hard a puzzle, or a pack of them, is
+  batch      Solve every puzzle of a file on a pool of threads
+  bench      Time solvers over a set of instances
+  play       Solve a puzzle by hand
+
+Puzzle options (solve, verify, hint, rate, bench, play):
+  --initial PATH       Puzzle file [default: src/configs/initial_state.json]
+  --goal PATH          Goal file [default: src/configs/final_state.json]
+
+Solver options (solve, batch, bench):
+  --solver NAMES       Comma-separated solvers, or \"all\" [default: astar]
+  --heuristic NAME     misplaced, manhattan, linear-conflict, weighted-manhattan
+                       (bench takes a comma-separated list)
+  --pruning NAME       none, inverse, automaton
+  --max-nodes N        Give up after N expansions (ucs, ida, ida-parallel, fringe)
+  --time-limit SECS    Give up after SECS seconds (same solvers)
+  --trace              Print the search as it goes (bfs)
+
+solve:
+  --format FORMAT      text or json [default: text]
+  --animate            Replay the solutions side by side
+  --delay MS           Delay between animation steps [default: 200]
+  --results            Also write the solutions to results.md
+  --grade              Search for the optimum to grade solutions against when
+                       it cannot be looked up (3x3 boards with unit costs);
+                       --max-nodes and --time-limit bound the search
+  --good-ratio R       Longest solution, as a multiple of the optimal length
+                       (or cost, with move costs), still graded good
+                       [default: 1.2]
+  --fair-ratio R       Same for fair; longer ones are poor [default: 1.5]
+  --shorten            Cut cycles and detours out of every solution
+  --window N           Moves re-solved at a time when shortening [default: 12]
+
+generate:
+  --size N             Board width [default: 3]
+  --moves N            Scramble the goal with a random walk of N moves that
+                       never undoes the previous one [default: 50]
+  --distance K         A puzzle whose optimal solution is exactly K moves
+  --uniform            A uniformly random solvable permutation
+  --seed N             Random seed, printed to stderr [default: current time]
+  --count N            Number of puzzles; more than one are written as JSON
+                       Lines [default: 1]
+  --output PATH        Write to PATH instead of stdout
+
+batch:
+  --input PATH         JSON Lines, a JSON array, or {\"puzzles\": [...]}
+  --goal PATH          Goal for puzzles without their own [default: standard]
+  --threads N          Worker threads [default: available cores]
+  --output PATH        Write the JSON Lines results to PATH instead of stdout
+
+verify:
+  --moves MOVES        Moves of the blank, e.g. \"ULDR\"
+  --moves-file PATH    Read the moves from a file instead: move letters, JSON
+                       with \"moves\", or the output of solve --format json
+
+hint:
+  --explain            Show what every legal move does to the heuristic and
+                       to the distance to the goal
+  --heuristic NAME     Heuristic to explain with [default: manhattan]
+
+rate:
+  --input PATH         Rate every puzzle of a file, as for batch, writing
+                       JSON Lines; --goal is then the default goal
+  --format FORMAT      text or json, for a single puzzle [default: text]
+
+bench:
+  --set NAME           8-puzzle or korf100 [default: 8-puzzle, or the puzzle
+                       given with --initial]
+  --first N            Only the first N instances of the set
+  --runs N             Runs per instance, the median time is kept [default: 5]
+  --format FORMAT      text or json [default: text]
+
+play:
+  Arrows or WASD move the blank, u undoes, h shows a hint, S lets the
+  solver finish, q quits. Keys are read a line at a time when the input
+  is not a terminal.
+  --delay MS           Delay between the solver's moves [default: 200]
+
+Exit codes: 0 success, 1 no solution or rejected moves, 2 usage error,
+3 unreadable or invalid input.";
+
+const DEFAULT_INITIAL: &str = "src/configs/initial_state.json";
+const DEFAULT_GOAL: &str = "src/configs/final_state.json";
+
+const EXIT_UNSOLVED: u8 = 1;
+const EXIT_USAGE: u8 = 2;
+const EXIT_INPUT: u8 = 3;
+
+#[derive(Debug)]
+enum Failure {
+    Usage(String),
+    Input(String),
+}
+
+type CommandResult = Result<ExitCode, Failure>;
+
+fn outcome(success: bool) -> ExitCode {
+    if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(EXIT_UNSOLVED)
+    }
+}
+
+// Command-line options of one command: `--name value`, `--name=value` or `--flag`
+struct Options {
+    values: HashMap<String, Vec<String>>,
+}
+
+impl Options {
+    fn parse(args: &[String], valued: &[&str], flags: &[&str]) -> Result<Options, Failure> {
+        let mut values: HashMap<String, Vec<String>> = HashMap::new();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let Some(name) = arg.strip_prefix("--") else {
+                return Err(Failure::Usage(format!("unexpected argument '{}'", arg)));
+            };
+            let (name, inline) = match name.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (name, None),
+            };
+
+            let value = if valued.contains(&name) {
+                match inline.or_else(|| args.next().cloned()) {
+                    Some(value) => value,
+                    None => return Err(Failure::Usage(format!("--{} needs a value", name))),
+                }
+            } else if flags.contains(&name) && inline.is_none() {
+                String::new()
+            } else {
+                return Err(Failure::Usage(format!("unknown option --{}", name)));
+            };
+            values.entry(name.to_string()).or_default().push(value);
+        }
+        Ok(Options { values })
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name)?.last().map(String::as_str)
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.values.contains_key(name)
+    }
+
+    fn parsed<T: FromStr>(&self, name: &str) -> Result<Option<T>, Failure> {
+        self.get(name)
+            .map(|value| {
+                value.parse().map_err(|_| {
+                    Failure::Usage(format!("invalid value '{}' for --{}", value, name))
+                })
+            })
+            .transpose()
+    }
+}
+
+const PUZZLE_OPTIONS: [&str; 2] = ["initial", "goal"];
+const SOLVER_OPTIONS: [&str; 5] = ["solver", "heuristic", "pruning", "max-nodes", "time-limit"];
+
+fn load_puzzle(options: &Options) -> Result<Board, Failure> {
+    load_board(
+        options.get("initial").unwrap_or(DEFAULT_INITIAL),
+        options.get("goal").unwrap_or(DEFAULT_GOAL),
+    )
+    .map_err(Failure::Input)
+}
+
+fn parse_heuristic(name: &str) -> Result<Heuristic, Failure> {
+    match name {
+        "misplaced" => Ok(Heuristic::MisplacedTiles),
+        "manhattan" => Ok(Heuristic::Manhattan),
+        "linear-conflict" => Ok(Heuristic::LinearConflict),
+        "weighted-manhattan" => Ok(Heuristic::WeightedManhattan),
+        _ => Err(Failure::Usage(format!("unknown heuristic '{}'", name))),
+    }
+}
+
+fn parse_pruning(name: &str) -> Result<MovePruning, Failure> {
+    match name {
+        "none" => Ok(MovePruning::None),
+        "inverse" => Ok(MovePruning::Inverse),
+        "automaton" => Ok(MovePruning::Automaton),
+        _ => Err(Failure::Usage(format!("unknown pruning '{}'", name))),
+    }
+}
+
+fn parse_solvers(options: &Options) -> Result<Vec<SolverKind>, Failure> {
+    let names = options.values.get("solver").cloned().unwrap_or_default();
+    if names.is_empty() {
+        return Ok(vec![SolverKind::AStar]);
+    }
+    let mut solvers = Vec::new();
+    for name in names.iter().flat_map(|names| names.split(',')) {
+        match name.trim() {
+            "all" => solvers.extend(SolverKind::ALL),
+            name => solvers.push(
+                SolverKind::from_name(name)
+                    .ok_or_else(|| Failure::Usage(format!("unknown solver '{}'", name)))?,
+            ),
+        }
+    }
+    Ok(solvers)
+}
+
+fn parse_solver_options(options: &Options) -> Result<SolverOptions, Failure> {
+    let mut limits = SearchLimits::default();
+    if let Some(max_nodes) = options.parsed::<usize>("max-nodes")? {
+        limits = limits.with_max_nodes(max_nodes);
+    }
+    if let Some(seconds) = options.parsed::<f64>("time-limit")? {
+        let limit = Duration::try_from_secs_f64(seconds)
+            .map_err(|_| Failure::Usage(format!("invalid time limit '{}'", seconds)))?;
+        limits = limits.with_time_limit(limit);
+    }
+
+    Ok(SolverOptions {
+        heuristic: options
+            .get("heuristic")
+            .map_or(Ok(Heuristic::default()), parse_heuristic)?,
+        pruning: options
+            .get("pruning")
+            .map_or(Ok(MovePruning::default()), parse_pruning)?,
+        limits,
+        trace: options.flag("trace"),
+    })
+}
+
+fn timed_solve(
+    kind: SolverKind,
+    board: &Board,
+    options: &SolverOptions,
+) -> (Option<SolutionInfo>, Duration) {
+    let start = Instant::now();
+    let solution = kind.solve(board, options);
+    (solution, start.elapsed())
+}
+
+fn parse_thresholds(options: &Options) -> Result<QualityThresholds, Failure> {
+    let defaults = QualityThresholds::default();
+    let good = options.parsed("good-ratio")?.unwrap_or(defaults.good);
+    let fair = options
+        .parsed("fair-ratio")?
+        .unwrap_or(defaults.fair.max(good));
+    if !(1.0..=fair).contains(&good) {
+        return Err(Failure::Usage(
+            "ratios must satisfy 1 <= --good-ratio <= --fair-ratio".to_string(),
+        ));
+    }
+    Ok(QualityThresholds { good, fair })
+}
+
+// Cheapest cost to the goal, the fewest moves with unit costs. A 3x3 board
+// with unit costs is a lookup in the shared distance table; anything else
+// takes a search, only run when asked for and within the solver limits.
+fn optimal_cost(board: &Board, search: bool, limits: &SearchLimits) -> Option<u32> {
+    let unit = board.get_move_cost().is_unit();
+    if board.get_size() == 3 && unit {
+        return DistanceTable::shared(board).distance(board).map(u32::from);
+    }
+    if !search {
+        return None;
+    }
+    let heuristic = if unit {
+        Heuristic::LinearConflict
+    } else {
+        Heuristic::WeightedManhattan
+    };
+    IDAStarSolver::new(board.clone())
+        .with_heuristic(heuristic)
+        .with_pruning(MovePruning::Automaton)
+        .with_limits(limits.clone())
+        .solve(None)
+        .map(|solution| solution.total_cost)
+}
+
+fn solve_command(args: &[String]) -> CommandResult {
+    let valued = [
+        &PUZZLE_OPTIONS[..],
+        &SOLVER_OPTIONS[..],
+        &["format", "delay", "good-ratio", "fair-ratio", "window"],
+    ]
+    .concat();
+    let options = Options::parse(
+        args,
+        &valued,
+        &["animate", "results", "trace", "shorten", "grade"],
+    )?;
+    let board = load_puzzle(&options)?;
+    let solvers = parse_solvers(&options)?;
+    let solver_options = parse_solver_options(&options)?;
+    let json = match options.get("format").unwrap_or("text") {
+        "text" => false,
+        "json" => true,
+        format => return Err(Failure::Usage(format!("unknown format '{}'", format))),
+    };
+    let delay = Duration::from_millis(options.parsed("delay")?.unwrap_or(200));
+
+    if !board.is_solvable() {
+        if json {
+            println!("{}", json!({ "solvable": false, "results": [] }));
+        } else {
+            eprintln!("The puzzle cannot reach its goal");
+        }
+        return Ok(ExitCode::from(EXIT_UNSOLVED));
+    }
+
+    let thresholds = parse_thresholds(&options)?;
+    let optimal = optimal_cost(&board, options.flag("grade"), &solver_options.limits);
+    // Lengths mean nothing against a cheapest cost when moves have prices
+    let grade = |solution: SolutionInfo| {
+        if board.get_move_cost().is_unit() {
+            solution.with_optimal_length(optimal.map(|cost| cost as usize), &thresholds)
+        } else {
+            solution.with_optimal_cost(optimal, &thresholds)
+        }
+    };
+    let window = options.parsed("window")?;
+    let shortener = options.flag("shorten").then(|| {
+        let shortener = PathShortener::new(board.clone());
+        match window {
+            Some(window) => shortener.with_window(window),
+            None => shortener,
+        }
+    });
+    let mut reports: Vec<Option<ShorteningReport>> = Vec::new();
+    let method_states: Vec<MethodState> = solvers
         .iter()
-        .map(|row| {
-            row.as_array()
-                .expect("Invalid row format")
-                .iter()
-                .map(|x| x.as_i64().expect("Invalid number") as i32)
-                .collect()
+        .map(|&kind| {
+            let (mut solution, time_taken) = timed_solve(kind, &board, &solver_options);
+            let mut report = None;
+            if let (Some(shortener), Some(found)) = (&shortener, &solution) {
+                // Solvers only return moves that reach the goal
+                let (shortened, saved) = shortener.shorten(found).unwrap();
+                solution = Some(shortened);
+                report = Some(saved);
+            }
+            reports.push(report);
+            MethodState {
+                name: kind.label().to_string(),
+                board: board.clone(),
+                current_step: 0,
+                solution: solution.map(grade),
+                time_taken,
+            }
         })
         .collect();
+    let states: Vec<&MethodState> = method_states.iter().collect();
+
+    if options.flag("animate") {
+        let max_steps = states
+            .iter()
+            .filter_map(|state| state.solution.as_ref())
+            .map(|sol| sol.moves.len())
+            .max()
+            .unwrap_or(0);
+        for step in 0..max_steps {
+            print_side_by_side(&states, step);
+            thread::sleep(delay);
+        }
+    }
+    if options.flag("results") {
+        write_results_to_file(&states)
+            .map_err(|err| Failure::Input(format!("cannot write results.md: {}", err)))?;
+    }
+
+    if json {
+        let results: Vec<Value> = solvers
+            .iter()
+            .zip(&method_states)
+            .zip(&reports)
+            .map(|((kind, state), report)| solution_json(*kind, state, report.as_ref()))
+            .collect();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&json!({ "solvable": true, "results": results })).unwrap()
+        );
+    } else {
+        for (state, report) in method_states.iter().zip(&reports) {
+            print_solution(state, report.as_ref());
+        }
+    }
 
-    Board::new(board_state)
+    Ok(outcome(
+        method_states.iter().all(|state| state.solution.is_some()),
+    ))
 }
 
-fn solve_puzzle(initial_board: Board, _goal_board: Board) -> io::Result<()> {
-    let config = Config {
-        iteration_delay: Duration::from_millis(200), // Fast speed
+fn solution_json(
+    kind: SolverKind,
+    state: &MethodState,
+    report: Option<&ShorteningReport>,
+) -> Value {
+    let mut result = match &state.solution {
+        Some(solution) => solution_to_json(solution),
+        None => json!({}),
     };
+    result["solver"] = json!(kind.name());
+    result["solved"] = json!(state.solution.is_some());
+    if let Some(solution) = &state.solution {
+        result["quality"] = json!(solution.quality.name());
+        result["optimal_length"] = json!(solution.optimal_length);
+        result["optimal_cost"] = json!(solution.optimal_cost);
+    }
+    if let Some(report) = report {
+        result["shortening"] = json!({
+            "original_length": report.original_length,
+            "original_cost": report.original_cost,
+            "cycles_removed": report.cycles_removed,
+            "windows_replaced": report.windows_replaced,
+            "moves_saved": report.moves_saved(),
+            "cost_saved": report.cost_saved(),
+        });
+    }
+    result["time_ms"] = json!(state.time_taken.as_secs_f64() * 1000.0);
+    result
+}
 
-    // Initialize solvers
-    let dfs_solver = DFSSolver::new_with_goal(initial_board.clone());
-    let bfs_solver = BFSSolver::new_with_goal(initial_board.clone());
-    let astar_solver = AStarSolver::new_with_goal(initial_board.clone());
+fn print_solution(state: &MethodState, report: Option<&ShorteningReport>) {
+    match &state.solution {
+        Some(solution) => {
+            let mut line = format!(
+                "{}: {} moves in {:?}, {}",
+                state.name,
+                solution.moves.len(),
+                state.time_taken,
+                solution.quality.to_colored_string()
+            );
+            if let Some(optimal) = solution.optimal_length {
+                line.push_str(&format!(" (optimal {})", optimal));
+            }
+            if let Some(optimal) = solution.optimal_cost {
+                line.push_str(&format!(" (cheapest cost {})", optimal));
+            }
+            if solution.total_cost != solution.moves.len() as u32 {
+                line.push_str(&format!(", cost {}", solution.total_cost));
+            }
+            if solution.stats.nodes_expanded > 0 {
+                line.push_str(&format!(
+                    ", {} nodes expanded",
+                    solution.stats.nodes_expanded
+                ));
+            }
+            println!("{}", line);
+            if let Some(report) = report.filter(|report| report.moves_saved() > 0) {
+                println!(
+                    "  shortened from {} moves: {} cycles removed, {} windows re-solved, {} moves saved",
+                    report.original_length,
+                    report.cycles_removed,
+                    report.windows_replaced,
+                    report.moves_saved()
+                );
+            }
+            if !solution.moves.is_empty() {
+                println!("  {}", format_moves(&solution.moves));
+            }
+        }
+        None => println!("{}: no solution in {:?}", state.name, state.time_taken),
+    }
+}
 
-    // Solve with all methods and measure time
-    let start = Instant::now();
-    let dfs_solution = dfs_solver.solve(None);
-    let dfs_time = start.elapsed();
+// Pretty JSON with each board row kept on one line
+fn puzzle_text(puzzle: &Value) -> String {
+    let rows: Vec<String> = puzzle["board"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|row| format!("    {}", row).replace(',', ", "))
+        .collect();
+    format!(
+        "{{\n  \"size\": {},\n  \"board\": [\n{}\n  ]\n}}",
+        puzzle["size"],
+        rows.join(",\n")
+    )
+}
 
-    let start = Instant::now();
-    let bfs_solution = bfs_solver.solve(None);
-    let bfs_time = start.elapsed();
+fn generate_command(args: &[String]) -> CommandResult {
+    let valued = ["size", "moves", "distance", "seed", "count", "output"];
+    let options = Options::parse(args, &valued, &["uniform"])?;
+    let size: usize = options.parsed("size")?.unwrap_or(3);
+    if size < 2 {
+        return Err(Failure::Usage("--size must be at least 2".to_string()));
+    }
+    let seed: u64 = match options.parsed("seed")? {
+        Some(seed) => seed,
+        None => SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(1, |time| time.as_nanos() as u64),
+    };
+    let count: usize = options.parsed("count")?.unwrap_or(1);
+    let moves: Option<usize> = options.parsed("moves")?;
+    let distance: Option<usize> = options.parsed("distance")?;
+    if [moves.is_some(), distance.is_some(), options.flag("uniform")]
+        .iter()
+        .filter(|&&given| given)
+        .count()
+        > 1
+    {
+        return Err(Failure::Usage(
+            "give at most one of --moves, --distance and --uniform".to_string(),
+        ));
+    }
 
-    let start = Instant::now();
-    let astar_solution = astar_solver.solve(None);
-    let astar_time = start.elapsed();
-
-    // Create method states
-    let method_states = vec![
-        MethodState {
-            board: initial_board.clone(),
-            current_step: 0,
-            solution: dfs_solution,
-            time_taken: dfs_time,
-        },
-        MethodState {
-            board: initial_board.clone(),
-            current_step: 0,
-            solution: bfs_solution,
-            time_taken: bfs_time,
-        },
-        MethodState {
-            board: initial_board,
-            current_step: 0,
-            solution: astar_solution,
-            time_taken: astar_time,
-        },
-    ];
-
-    // Find maximum number of steps
-    let max_steps = method_states
+    let mut generator = PuzzleGenerator::new(size).with_seed(seed);
+    let mut puzzles = Vec::with_capacity(count);
+    for _ in 0..count {
+        let board = match distance {
+            Some(distance) => generator.at_distance(distance).ok_or_else(|| {
+                Failure::Input(format!(
+                    "no {}x{} puzzle is {} moves from the goal",
+                    size, size, distance
+                ))
+            })?,
+            None if options.flag("uniform") => generator.random_permutation(),
+            None => generator.random_walk(moves.unwrap_or(50)),
+        };
+        let mut puzzle = board_to_json(&board);
+        if let Some(distance) = distance {
+            puzzle["optimal_length"] = json!(distance);
+        }
+        puzzles.push(puzzle);
+    }
+    // Reruns with this seed give the same puzzles
+    eprintln!("seed: {}", seed);
+
+    // One puzzle in the usual layout, several as JSON Lines for `batch`
+    let text = match &puzzles[..] {
+        [puzzle] => puzzle_text(puzzle) + "\n",
+        _ => puzzles
+            .iter()
+            .enumerate()
+            .map(|(i, puzzle)| {
+                let mut puzzle = puzzle.clone();
+                puzzle["id"] = json!(i + 1);
+                puzzle.to_string() + "\n"
+            })
+            .collect(),
+    };
+    match options.get("output") {
+        Some(path) => fs::write(path, text)
+            .map_err(|err| Failure::Input(format!("cannot write {}: {}", path, err)))?,
+        None => print!("{}", text),
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn verify_command(args: &[String]) -> CommandResult {
+    let valued = [&PUZZLE_OPTIONS[..], &["moves", "moves-file"]].concat();
+    let options = Options::parse(args, &valued, &[])?;
+    let board = load_puzzle(&options)?;
+    let solutions = match (options.get("moves"), options.get("moves-file")) {
+        (Some(moves), None) => vec![SolutionEntry {
+            solver: None,
+            moves: parse_moves(moves).map_err(Failure::Input)?,
+        }],
+        (None, Some(path)) => read_solutions(path).map_err(Failure::Input)?,
+        _ => {
+            return Err(Failure::Usage(
+                "give exactly one of --moves and --moves-file".to_string(),
+            ))
+        }
+    };
+    if solutions.is_empty() {
+        return Err(Failure::Input("no moves to verify".to_string()));
+    }
+
+    let mut all_valid = true;
+    for SolutionEntry { solver, moves } in solutions {
+        let prefix = solver.map_or(String::new(), |name| format!("{}: ", name));
+        match verify(&board, board.goal_states(), &moves) {
+            Ok(()) => println!(
+                "{}Valid: {} moves, cost {}",
+                prefix,
+                moves.len(),
+                board.path_cost(&moves)
+            ),
+            Err(err) => {
+                all_valid = false;
+                println!("{}Invalid: {}", prefix, err);
+            }
+        }
+    }
+    Ok(outcome(all_valid))
+}
+
+fn hint_command(args: &[String]) -> CommandResult {
+    let options = Options::parse(
+        args,
+        &[&PUZZLE_OPTIONS[..], &["heuristic"]].concat(),
+        &["explain"],
+    )?;
+    let board = load_puzzle(&options)?;
+    if board.is_goal() {
+        println!("Already solved");
+        return Ok(ExitCode::SUCCESS);
+    }
+    let hinter = Hinter::new().with_heuristic(
+        options
+            .get("heuristic")
+            .map_or(Ok(Heuristic::default()), parse_heuristic)?,
+    );
+    let Some((dir, remaining)) = hinter.hint(&board) else {
+        println!("The goal cannot be reached from this board");
+        return Ok(ExitCode::from(EXIT_UNSOLVED));
+    };
+
+    println!("Move the blank {:?} ({} moves to go)", dir, remaining);
+    if options.flag("explain") {
+        for assessment in hinter.explain(&board) {
+            let name = format!("{:<5}", format!("{:?}", assessment.direction));
+            let verdict = if assessment.optimal {
+                format!("{} [optimal]", name).green()
+            } else {
+                format!("{} [worse]  ", name).red()
+            };
+            println!("- {} | {}", verdict, assessment.reason());
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn print_rating(rating: &Rating) {
+    println!(
+        "Optimal length: {} ({})",
+        rating.optimal_length,
+        rating.difficulty.name()
+    );
+    println!("A* nodes expanded: {}\n", rating.nodes_expanded);
+    println!("{:<18} {:>6} {:>6}", "Heuristic", "Value", "Gap");
+    for estimate in &rating.estimates {
+        println!(
+            "{:<18} {:>6} {:>6}",
+            estimate.name, estimate.value, estimate.gap
+        );
+    }
+}
+
+fn rate_command(args: &[String]) -> CommandResult {
+    let valued = [&PUZZLE_OPTIONS[..], &["input", "format"]].concat();
+    let options = Options::parse(args, &valued, &[])?;
+    let json = match options.get("format").unwrap_or("text") {
+        "text" => false,
+        "json" => true,
+        format => return Err(Failure::Usage(format!("unknown format '{}'", format))),
+    };
+
+    // A whole pack: one JSON line per puzzle
+    if let Some(input) = options.get("input") {
+        let goal = options
+            .get("goal")
+            .map(read_json)
+            .transpose()
+            .map_err(Failure::Input)?;
+        let mut all_rated = true;
+        for (index, entry) in read_puzzles(input, goal.as_ref())
+            .map_err(Failure::Input)?
+            .iter()
+            .enumerate()
+        {
+            let mut line = match entry.board.as_ref().map(rate) {
+                Ok(Some(rating)) => rating.to_json(),
+                Ok(None) => json!({ "error": "unsolvable" }),
+                Err(reason) => json!({ "error": reason }),
+            };
+            all_rated &= line.get("error").is_none();
+            line["index"] = json!(index);
+            if let Some(id) = &entry.id {
+                line["id"] = id.clone();
+            }
+            println!("{}", line);
+        }
+        return Ok(outcome(all_rated));
+    }
+
+    let board = load_puzzle(&options)?;
+    let Some(rating) = rate(&board) else {
+        if json {
+            println!("{}", json!({ "error": "unsolvable" }));
+        } else {
+            eprintln!("The puzzle cannot reach its goal");
+        }
+        return Ok(ExitCode::from(EXIT_UNSOLVED));
+    };
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&rating.to_json()).unwrap()
+        );
+    } else {
+        print_rating(&rating);
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn batch_command(args: &[String]) -> CommandResult {
+    let valued = [&SOLVER_OPTIONS[..], &["input", "goal", "threads", "output"]].concat();
+    let options = Options::parse(args, &valued, &["trace"])?;
+    let input = options
+        .get("input")
+        .ok_or_else(|| Failure::Usage("batch needs --input".to_string()))?;
+    let goal = options
+        .get("goal")
+        .map(read_json)
+        .transpose()
+        .map_err(Failure::Input)?;
+    let puzzles = read_puzzles(input, goal.as_ref()).map_err(Failure::Input)?;
+
+    let solvers = parse_solvers(&options)?;
+    let [kind] = solvers[..] else {
+        return Err(Failure::Usage("batch runs a single solver".to_string()));
+    };
+    let mut batch = BatchSolver::new(kind).with_options(parse_solver_options(&options)?);
+    if let Some(threads) = options.parsed("threads")? {
+        batch = batch.with_threads(threads);
+    }
+
+    let started = Instant::now();
+    let records = batch.solve_all(&puzzles);
+    let lines: String = records
+        .iter()
+        .map(|record| record.to_json().to_string() + "\n")
+        .collect();
+    match options.get("output") {
+        Some(path) => fs::write(path, lines)
+            .map_err(|err| Failure::Input(format!("cannot write {}: {}", path, err)))?,
+        None => print!("{}", lines),
+    }
+
+    let solved = records.iter().filter(|record| record.is_solved()).count();
+    eprintln!(
+        "Solved {} of {} puzzles in {:.2?}",
+        solved,
+        records.len(),
+        started.elapsed()
+    );
+    Ok(outcome(solved == records.len()))
+}
+
+fn parse_heuristics(options: &Options) -> Result<Vec<Heuristic>, Failure> {
+    let names = options.values.get("heuristic").cloned().unwrap_or_default();
+    if names.is_empty() {
+        return Ok(vec![Heuristic::default()]);
+    }
+    names
         .iter()
-        .filter_map(|state| state.solution.as_ref())
-        .map(|sol| sol.moves.len())
-        .max()
-        .unwrap_or(0);
+        .flat_map(|names| names.split(','))
+        .map(|name| parse_heuristic(name.trim()))
+        .collect()
+}
+
+fn bench_instances(options: &Options) -> Result<(String, Vec<Instance>), Failure> {
+    let mut instances = match options.get("set") {
+        Some(name) => {
+            let set = InstanceSet::from_name(name)
+                .ok_or_else(|| Failure::Usage(format!("unknown instance set '{}'", name)))?;
+            (set.name().to_string(), set.instances())
+        }
+        // A single puzzle of the user's, of unknown optimal length
+        None if options.get("initial").is_some() || options.get("goal").is_some() => {
+            let board = load_puzzle(options)?;
+            let instance = Instance {
+                id: 1,
+                board,
+                optimal_length: None,
+            };
+            ("puzzle".to_string(), vec![instance])
+        }
+        None => {
+            let set = InstanceSet::EightPuzzle;
+            (set.name().to_string(), set.instances())
+        }
+    };
+    if let Some(first) = options.parsed::<usize>("first")? {
+        instances.1.truncate(first);
+    }
+    Ok(instances)
+}
 
-    // Display side by side visualization
-    for step in 0..max_steps {
-        print_side_by_side(&method_states.iter().collect::<Vec<_>>(), step);
-        thread::sleep(config.iteration_delay);
+fn bench_command(args: &[String]) -> CommandResult {
+    let valued = [
+        &PUZZLE_OPTIONS[..],
+        &SOLVER_OPTIONS[..],
+        &["runs", "set", "first", "format"],
+    ]
+    .concat();
+    let mut options = Options::parse(args, &valued, &["trace"])?;
+    let (set, instances) = bench_instances(&options)?;
+    let solvers = parse_solvers(&options)?;
+    let heuristics = parse_heuristics(&options)?;
+    // Every heuristic gets its own row, the shared options take none
+    options.values.remove("heuristic");
+    let solver_options = parse_solver_options(&options)?;
+    let runs: usize = options.parsed("runs")?.unwrap_or(5).max(1);
+    let json = match options.get("format").unwrap_or("text") {
+        "text" => false,
+        "json" => true,
+        format => return Err(Failure::Usage(format!("unknown format '{}'", format))),
+    };
+
+    let benchmark = Benchmark::new(instances)
+        .with_options(solver_options)
+        .with_runs(runs);
+    let mut configurations = Vec::new();
+    for kind in solvers {
+        if kind.uses_heuristic() {
+            configurations.extend(heuristics.iter().map(|&heuristic| (kind, Some(heuristic))));
+        } else {
+            configurations.push((kind, None));
+        }
+    }
+
+    if !json {
+        println!(
+            "{}: {} instances, median of {} runs each\n",
+            set,
+            benchmark.instances().len(),
+            runs
+        );
+        println!(
+            "{:<14} {:<18} {:>7} {:>7} {:>12} {:>12} {:>12} {:>8}",
+            "Solver", "Heuristic", "Solved", "Optimal", "Median", "Total", "Nodes", "Length"
+        );
+    }
+    let mut rows = Vec::new();
+    for (kind, heuristic) in configurations {
+        let row = benchmark.run(kind, heuristic);
+        if !json {
+            println!(
+                "{:<14} {:<18} {:>7} {:>7} {:>12} {:>12} {:>12} {:>8}",
+                kind.label(),
+                row.heuristic_name(),
+                format!("{}/{}", row.solved, row.instances),
+                row.optimal,
+                format!("{:.2?}", row.median_time),
+                format!("{:.2?}", row.total_time),
+                row.nodes_expanded,
+                row.total_length
+            );
+        }
+        rows.push(row);
+    }
+
+    if json {
+        let report = json!({
+            "version": env!("CARGO_PKG_VERSION"),
+            "set": set,
+            "instances": benchmark.instances().len(),
+            "runs": runs,
+            "rows": rows.iter().map(BenchRow::to_json).collect::<Vec<_>>(),
+        });
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+    }
+    Ok(outcome(rows.iter().all(|row| row.solved == row.instances)))
+}
+
+fn play_command(args: &[String]) -> CommandResult {
+    let valued = [&PUZZLE_OPTIONS[..], &["delay"]].concat();
+    let options = Options::parse(args, &valued, &[])?;
+    let board = load_puzzle(&options)?;
+    if !board.is_solvable() {
+        eprintln!("The puzzle cannot reach its goal");
+        return Ok(ExitCode::from(EXIT_UNSOLVED));
+    }
+    let delay = Duration::from_millis(options.parsed("delay")?.unwrap_or(200));
+
+    let mut game = Game::new(board);
+    let result = play(&mut game, delay)
+        .map_err(|err| Failure::Input(format!("cannot play in this terminal: {}", err)))?;
+    println!();
+    Ok(outcome(result == PlayOutcome::Solved))
+}
+
+fn run_command(command: &str, args: &[String]) -> CommandResult {
+    match command {
+        "solve" => solve_command(args),
+        "generate" => generate_command(args),
+        "verify" => verify_command(args),
+        "hint" => hint_command(args),
+        "rate" => rate_command(args),
+        "batch" => batch_command(args),
+        "bench" => bench_command(args),
+        "play" => play_command(args),
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(ExitCode::SUCCESS)
+        }
+        _ => Err(Failure::Usage(format!("unknown command '{}'", command))),
     }
+}
 
-    // Write final results to file
-    write_results_to_file(&method_states.iter().collect::<Vec<_>>())?;
+// Reports a failed command and turns it into its exit code
+fn exit_code(result: CommandResult) -> ExitCode {
+    match result {
+        Ok(code) => code,
+        Err(Failure::Usage(message)) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            ExitCode::from(EXIT_USAGE)
+        }
+        Err(Failure::Input(message)) => {
+            eprintln!("error: {}", message);
+            ExitCode::from(EXIT_INPUT)
+        }
+    }
+}
 
-    println!("\nResults have been written to results.md");
-    Ok(())
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some((command, rest)) = args.split_first() else {
+        eprintln!("{}", USAGE);
+        return ExitCode::from(EXIT_USAGE);
+    };
+
+    if rest.iter().any(|arg| arg == "--help") {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+
+    exit_code(run_command(command, rest))
 }
 
-fn main() -> io::Result<()> {
-    // Load initial and goal states from config files
-    let initial_board = load_board_from_file("src/configs/initial_state.json");
-    let goal_board = load_board_from_file("src/configs/final_state.json");
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use taquin::MoveCost;
+
+    fn args(text: &str) -> Vec<String> {
+        text.split_whitespace().map(String::from).collect()
+    }
+
+    fn options(text: &str) -> Result<Options, Failure> {
+        Options::parse(&args(text), &["solver", "delay"], &["animate"])
+    }
+
+    fn usage_error(result: Result<impl Sized, Failure>) -> String {
+        match result {
+            Err(Failure::Usage(message)) => message,
+            Err(Failure::Input(message)) => panic!("input error: {}", message),
+            Ok(_) => panic!("expected a usage error"),
+        }
+    }
+
+    #[test]
+    fn test_options_parse_values_and_flags() {
+        let parsed = options("--solver astar --delay=50 --animate --solver ida").unwrap();
+        assert_eq!(parsed.get("solver"), Some("ida"));
+        assert_eq!(parsed.values["solver"], ["astar", "ida"]);
+        assert_eq!(parsed.parsed::<u64>("delay").unwrap(), Some(50));
+        assert!(parsed.flag("animate"));
+        assert!(!parsed.flag("results"));
+
+        assert_eq!(usage_error(options("--solver")), "--solver needs a value");
+        assert_eq!(
+            usage_error(options("--animate=yes")),
+            "unknown option --animate"
+        );
+        assert_eq!(
+            usage_error(options("--colour red")),
+            "unknown option --colour"
+        );
+        assert_eq!(usage_error(options("astar")), "unexpected argument 'astar'");
+        assert_eq!(
+            usage_error(options("--delay soon").unwrap().parsed::<u64>("delay")),
+            "invalid value 'soon' for --delay"
+        );
+    }
+
+    #[test]
+    fn test_solvers_and_thresholds() {
+        let solvers = |text: &str| options(text).and_then(|parsed| parse_solvers(&parsed));
+        assert_eq!(solvers("").ok(), Some(vec![SolverKind::AStar]));
+        assert_eq!(solvers("--solver all").ok(), Some(SolverKind::ALL.to_vec()));
+        assert_eq!(
+            solvers("--solver dfs,ida --solver bfs").ok(),
+            Some(vec![SolverKind::Dfs, SolverKind::IdaStar, SolverKind::Bfs])
+        );
+        assert_eq!(
+            usage_error(solvers("--solver quantum")),
+            "unknown solver 'quantum'"
+        );
+
+        let thresholds = |text: &str| {
+            Options::parse(&args(text), &["good-ratio", "fair-ratio"], &[])
+                .and_then(|parsed| parse_thresholds(&parsed))
+        };
+        assert_eq!(thresholds("").ok(), Some(QualityThresholds::default()));
+        // A good ratio above the default fair one moves fair up with it
+        let raised = thresholds("--good-ratio 2").unwrap();
+        assert_eq!((raised.good, raised.fair), (2.0, 2.0));
+        assert!(usage_error(thresholds("--good-ratio 0.5")).starts_with("ratios must"));
+        assert!(
+            usage_error(thresholds("--good-ratio 1.4 --fair-ratio 1.3")).starts_with("ratios must")
+        );
+    }
 
-    // Run solvers with side-by-side visualization
-    solve_puzzle(initial_board, goal_board)
+    #[test]
+    fn test_optimum_is_searched_only_when_asked() {
+        let limits = SearchLimits::default();
+        let board = Board::new(vec![vec![4, 1, 3], vec![7, 2, 5], vec![0, 8, 6]]);
+        assert_eq!(optimal_cost(&board, false, &limits), Some(6));
+
+        let priced = board.with_move_cost(MoveCost::FaceValue);
+        assert_eq!(optimal_cost(&priced, false, &limits), None);
+        assert_eq!(optimal_cost(&priced, true, &limits), Some(25));
+        let bounded = SearchLimits::default().with_max_nodes(1);
+        assert_eq!(optimal_cost(&priced, true, &bounded), None);
+
+        let large = Board::new(Board::standard_goal(8));
+        assert_eq!(optimal_cost(&large, false, &limits), None);
+    }
+
+    fn run(text: &str) -> ExitCode {
+        let args = args(text);
+        exit_code(run_command(&args[0], &args[1..]))
+    }
+
+    // A puzzle file whose goal is out of reach
+    fn unsolvable_puzzle(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("taquin-{}.json", name));
+        fs::write(&path, r#"{"board": [[2, 1, 3], [4, 5, 6], [7, 8, 0]]}"#).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn test_unsolvable_puzzle_exits_unsolved() {
+        let puzzle = unsolvable_puzzle("unsolvable");
+        for command in ["solve", "play", "rate"] {
+            assert_eq!(
+                run(&format!("{} --initial {}", command, puzzle)),
+                ExitCode::from(EXIT_UNSOLVED),
+                "{}",
+                command
+            );
+        }
+    }
+
+    #[test]
+    fn test_exit_codes() {
+        assert_eq!(run("verify --moves DR"), ExitCode::SUCCESS);
+        assert_eq!(run("verify --moves UU"), ExitCode::from(EXIT_UNSOLVED));
+        assert_eq!(run("solve --solver quantum"), ExitCode::from(EXIT_USAGE));
+        assert_eq!(run("unknown"), ExitCode::from(EXIT_USAGE));
+        assert_eq!(
+            run("solve --initial /nonexistent/puzzle.json"),
+            ExitCode::from(EXIT_INPUT)
+        );
+    }
 }
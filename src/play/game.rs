@@ -0,0 +1,259 @@
+use std::time::{Duration, Instant};
+
+use crate::{Board, Direction, Heuristic, Hinter, SolverKind, SolverOptions};
+
+/// A command typed during a game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Key {
+    Move(Direction),
+    Undo,
+    Hint,
+    Solve,
+    Quit,
+}
+
+impl Key {
+    /// Letter keys: WASD move the blank, `u` undoes, `h` hints, `S` lets the
+    /// solver finish (lower-case `s` already moves down) and `q` quits.
+    pub fn from_char(c: char) -> Option<Key> {
+        match c {
+            'w' | 'W' => Some(Key::Move(Direction::Up)),
+            'a' | 'A' => Some(Key::Move(Direction::Left)),
+            's' => Some(Key::Move(Direction::Down)),
+            'd' | 'D' => Some(Key::Move(Direction::Right)),
+            'u' | 'U' => Some(Key::Undo),
+            'h' | 'H' => Some(Key::Hint),
+            'S' => Some(Key::Solve),
+            'q' | 'Q' => Some(Key::Quit),
+            _ => None,
+        }
+    }
+}
+
+/// State of a game played by hand: the board, the moves played so far and
+/// the clock, which starts with the first move.
+pub struct Game {
+    board: Board,
+    history: Vec<Direction>,
+    started: Option<Instant>,
+    finished: Option<Duration>,
+    hints: usize,
+    hinter: Hinter,
+    /// Whether the solver played any of the moves
+    assisted: bool,
+    message: String,
+}
+
+impl Game {
+    pub fn new(board: Board) -> Self {
+        Game {
+            board,
+            history: Vec::new(),
+            started: None,
+            finished: None,
+            hints: 0,
+            hinter: Hinter::new(),
+            assisted: false,
+            message: String::new(),
+        }
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Moves currently on the board; undone moves are not counted.
+    pub fn moves(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn hints(&self) -> usize {
+        self.hints
+    }
+
+    pub fn is_assisted(&self) -> bool {
+        self.assisted
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.board.is_goal()
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        match (self.started, self.finished) {
+            (_, Some(total)) => total,
+            (Some(started), None) => started.elapsed(),
+            (None, None) => Duration::ZERO,
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn set_message(&mut self, message: impl Into<String>) {
+        self.message = message.into();
+    }
+
+    fn record(&mut self, dir: Direction) {
+        self.started.get_or_insert_with(Instant::now);
+        self.history.push(dir);
+        if self.is_solved() {
+            self.finished = Some(self.elapsed());
+        }
+    }
+
+    /// Moves the blank, or leaves a message and returns false if it cannot.
+    pub fn play_move(&mut self, dir: Direction) -> bool {
+        if self.is_solved() {
+            return false;
+        }
+        match self.board.make_move(dir) {
+            Ok(()) => {
+                self.message.clear();
+                self.record(dir);
+                true
+            }
+            Err(_) => {
+                self.message = format!("Cannot move {:?}", dir);
+                false
+            }
+        }
+    }
+
+    pub fn undo(&mut self) -> bool {
+        if self.is_solved() {
+            return false;
+        }
+        match self.history.pop() {
+            Some(dir) => {
+                self.board.make_move(dir.opposite()).unwrap();
+                self.message.clear();
+                true
+            }
+            None => {
+                self.message = "Nothing to undo".to_string();
+                false
+            }
+        }
+    }
+
+    /// Optimal moves from the current board to the goal.
+    pub fn solution(&self) -> Option<Vec<Direction>> {
+        let options = SolverOptions {
+            heuristic: Heuristic::LinearConflict,
+            ..SolverOptions::default()
+        };
+        SolverKind::IdaStar
+            .solve(&self.board, &options)
+            .map(|solution| solution.moves)
+    }
+
+    /// First move of an optimal solution, also left as the message.
+    pub fn hint(&mut self) -> Option<Direction> {
+        if self.is_solved() {
+            return None;
+        }
+        self.hints += 1;
+        match self.hinter.hint(&self.board) {
+            Some((dir, remaining)) => {
+                self.message =
+                    format!("Hint: move the blank {:?} ({} moves to go)", dir, remaining);
+                Some(dir)
+            }
+            None => {
+                self.message = "No solution from here".to_string();
+                None
+            }
+        }
+    }
+
+    /// Plays a move on the player's behalf, as when the solver finishes.
+    pub fn play_solver_move(&mut self, dir: Direction) -> bool {
+        self.assisted = true;
+        let played = self.play_move(dir);
+        self.message = "The solver is playing".to_string();
+        played
+    }
+
+    /// The board, the counters and the last message.
+    pub fn render(&self) -> String {
+        let mut screen = format!("{}", self.board);
+        screen.push_str(&format!(
+            "Moves: {}   Time: {}s   Hints: {}\n",
+            self.moves(),
+            self.elapsed().as_secs(),
+            self.hints
+        ));
+        if self.is_solved() {
+            let by = if self.assisted {
+                " with the solver's help"
+            } else {
+                ""
+            };
+            screen.push_str(&format!(
+                "Solved in {} moves and {:.1?}{}!\n",
+                self.moves(),
+                self.elapsed(),
+                by
+            ));
+        } else {
+            screen.push_str("Arrows/WASD move the blank, u undo, h hint, S solve, q quit\n");
+            if !self.message.is_empty() {
+                screen.push_str(&format!("{}\n", self.message));
+            }
+        }
+        screen
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game() -> Game {
+        Game::new(Board::new(vec![
+            vec![1, 2, 3],
+            vec![4, 0, 6],
+            vec![7, 5, 8],
+        ]))
+    }
+
+    #[test]
+    fn test_moves_and_undo() {
+        let mut game = game();
+        assert!(game.play_move(Direction::Up));
+        assert!(game.play_move(Direction::Left));
+        assert_eq!(game.moves(), 2);
+        assert!(game.undo());
+        assert!(game.undo());
+        assert!(!game.undo());
+        assert_eq!(game.moves(), 0);
+        assert!(game.board().get_row(1).contains(&0));
+    }
+
+    #[test]
+    fn test_hint_leads_to_the_goal() {
+        let mut game = game();
+        while !game.is_solved() {
+            let dir = game.hint().unwrap();
+            assert!(game.play_move(dir));
+        }
+        assert_eq!((game.moves(), game.hints()), (2, 2));
+        assert!(!game.is_assisted());
+        assert!(game.hint().is_none());
+    }
+
+    #[test]
+    fn test_clock_stops_when_solved() {
+        let mut game = game();
+        assert_eq!(game.elapsed(), Duration::ZERO);
+        for dir in game.solution().unwrap() {
+            game.play_solver_move(dir);
+        }
+        assert!(game.is_solved() && game.is_assisted());
+        let time = game.elapsed();
+        assert_eq!(game.elapsed(), time);
+        assert!(!game.play_move(Direction::Up));
+    }
+}
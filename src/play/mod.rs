@@ -0,0 +1,4 @@
+mod game;
+mod terminal;
+pub use game::{Game, Key};
+pub use terminal::{play, PlayOutcome};
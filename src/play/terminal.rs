@@ -0,0 +1,165 @@
+use std::io::{self, Read, Write};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::Duration;
+
+use super::{Game, Key};
+use crate::common::visualization::clear_screen;
+use crate::Direction;
+
+// How often the clock is redrawn while waiting for a key
+const TICK: Duration = Duration::from_secs(1);
+
+/// How a game ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlayOutcome {
+    Solved,
+    Quit,
+}
+
+// Raw terminal mode for as long as it lives, so that keys arrive one at a
+// time without echo. `stty` saves and restores the settings.
+struct RawMode {
+    saved: String,
+}
+
+impl RawMode {
+    fn enable() -> Option<RawMode> {
+        let output = Command::new("stty")
+            .arg("-g")
+            .stdin(Stdio::inherit())
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let saved = String::from_utf8(output.stdout).ok()?.trim().to_string();
+        let status = Command::new("stty")
+            .args(["raw", "-echo"])
+            .stdin(Stdio::inherit())
+            .status()
+            .ok()?;
+        status.success().then_some(RawMode { saved })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = Command::new("stty")
+            .arg(&self.saved)
+            .stdin(Stdio::inherit())
+            .status();
+    }
+}
+
+// Arrow keys arrive as ESC [ A-D, Ctrl-C and Ctrl-D as bytes 3 and 4 in raw
+// mode; the end of the input quits
+fn read_keys(sender: Sender<Key>) {
+    let mut bytes = io::stdin().lock().bytes().map_while(Result::ok);
+    while let Some(byte) = bytes.next() {
+        let key = match byte {
+            0x1B => match (bytes.next(), bytes.next()) {
+                (Some(b'['), Some(b'A')) => Some(Key::Move(Direction::Up)),
+                (Some(b'['), Some(b'B')) => Some(Key::Move(Direction::Down)),
+                (Some(b'['), Some(b'C')) => Some(Key::Move(Direction::Right)),
+                (Some(b'['), Some(b'D')) => Some(Key::Move(Direction::Left)),
+                _ => None,
+            },
+            3 | 4 => Some(Key::Quit),
+            _ => Key::from_char(byte as char),
+        };
+        if let Some(key) = key {
+            if sender.send(key).is_err() {
+                return;
+            }
+        }
+    }
+    let _ = sender.send(Key::Quit);
+}
+
+// Redraws the whole screen in raw mode; otherwise prints the board and a
+// prompt below the previous one, as keys only arrive a line at a time
+fn draw(game: &Game, raw: bool) -> io::Result<()> {
+    if raw {
+        clear_screen();
+    }
+    let mut out = io::stdout().lock();
+    if raw {
+        write!(out, "{}", game.render().replace('\n', "\r\n"))?;
+    } else {
+        write!(out, "{}", game.render())?;
+        if !game.is_solved() {
+            write!(out, "> ")?;
+        }
+    }
+    out.flush()
+}
+
+fn next_key(keys: &Receiver<Key>, game: &Game, raw: bool) -> io::Result<Option<Key>> {
+    // Only draw once every queued key is handled
+    if let Ok(key) = keys.try_recv() {
+        return Ok(Some(key));
+    }
+    draw(game, raw)?;
+    if !raw {
+        return Ok(Some(keys.recv().unwrap_or(Key::Quit)));
+    }
+    match keys.recv_timeout(TICK) {
+        Ok(key) => Ok(Some(key)),
+        Err(RecvTimeoutError::Timeout) => Ok(None),
+        Err(RecvTimeoutError::Disconnected) => Ok(Some(Key::Quit)),
+    }
+}
+
+/// Plays `game` in the terminal until it is solved or the player quits.
+/// Without a terminal to put in raw mode, keys are read a line at a time.
+/// `delay` paces the solver's moves when it is asked to finish.
+pub fn play(game: &mut Game, delay: Duration) -> io::Result<PlayOutcome> {
+    let raw = RawMode::enable();
+    let animate = raw.is_some();
+    let (sender, keys) = mpsc::channel();
+    thread::spawn(move || read_keys(sender));
+
+    while !game.is_solved() {
+        let Some(key) = next_key(&keys, game, animate)? else {
+            continue;
+        };
+        match key {
+            Key::Move(dir) => {
+                game.play_move(dir);
+            }
+            Key::Undo => {
+                game.undo();
+            }
+            Key::Hint => {
+                if animate {
+                    game.set_message("Thinking...");
+                    draw(game, true)?;
+                }
+                game.hint();
+            }
+            Key::Solve => {
+                if animate {
+                    game.set_message("Solving...");
+                    draw(game, true)?;
+                }
+                let Some(moves) = game.solution() else {
+                    game.set_message("No solution from here");
+                    continue;
+                };
+                for dir in moves {
+                    game.play_solver_move(dir);
+                    if animate {
+                        draw(game, true)?;
+                        thread::sleep(delay);
+                    }
+                }
+            }
+            Key::Quit => return Ok(PlayOutcome::Quit),
+        }
+    }
+
+    draw(game, animate)?;
+    Ok(PlayOutcome::Solved)
+}
@@ -0,0 +1,2 @@
+mod rater;
+pub use rater::{rate, Difficulty, HeuristicEstimate, Rating};
@@ -0,0 +1,190 @@
+use serde_json::{json, Value};
+
+use crate::common::PatternDatabase;
+use crate::{AStarSolver, Board, Heuristic, Solver};
+
+/// Bucket of a puzzle's optimal length relative to the longest optimal
+/// solution any puzzle of its size has.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Difficulty {
+    Trivial,
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+}
+
+impl Difficulty {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Trivial => "trivial",
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+            Difficulty::Expert => "expert",
+        }
+    }
+
+    /// Fifths of the diameter: below 20% of it is trivial, 80% and up expert.
+    pub fn of_length(length: usize, size: usize) -> Difficulty {
+        match 5 * length / diameter(size).max(1) {
+            0 => Difficulty::Trivial,
+            1 => Difficulty::Easy,
+            2 => Difficulty::Medium,
+            3 => Difficulty::Hard,
+            _ => Difficulty::Expert,
+        }
+    }
+}
+
+// Longest optimal solution on a size x size board. Known up to 4x4; beyond,
+// size³ + size² stands in, close to the best known lower bound for 5x5
+fn diameter(size: usize) -> usize {
+    match size {
+        0 | 1 => 0,
+        2 => 6,
+        3 => 31,
+        4 => 80,
+        _ => size.pow(3) + size.pow(2),
+    }
+}
+
+/// A heuristic's estimate of a puzzle and how far short of optimal it falls.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HeuristicEstimate {
+    pub name: &'static str,
+    pub value: u32,
+    /// Optimal length minus the estimate
+    pub gap: u32,
+}
+
+/// How hard a puzzle is, measured on the puzzle itself.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rating {
+    pub optimal_length: usize,
+    /// Manhattan distance, linear conflicts and, up to 4x4, pattern databases
+    pub estimates: Vec<HeuristicEstimate>,
+    /// Expansions A* with linear conflicts needed to prove the optimum
+    pub nodes_expanded: usize,
+    pub difficulty: Difficulty,
+}
+
+impl Rating {
+    pub fn to_json(&self) -> Value {
+        let estimates: Vec<Value> = self
+            .estimates
+            .iter()
+            .map(|estimate| {
+                json!({
+                    "heuristic": estimate.name,
+                    "value": estimate.value,
+                    "gap": estimate.gap,
+                })
+            })
+            .collect();
+        json!({
+            "optimal_length": self.optimal_length,
+            "estimates": estimates,
+            "nodes_expanded": self.nodes_expanded,
+            "difficulty": self.difficulty.name(),
+        })
+    }
+}
+
+/// Rates `board`, or `None` if it cannot be solved. Lengths count moves;
+/// with move costs the length is that of the cheapest solution. A* keeps
+/// every state it reaches, so hard 4x4 puzzles take a lot of memory.
+pub fn rate(board: &Board) -> Option<Rating> {
+    if !board.is_solvable() {
+        return None;
+    }
+    let solution = AStarSolver::new(board.clone())
+        .with_heuristic(Heuristic::LinearConflict)
+        .solve(None)?;
+    let optimal_length = solution.moves.len();
+
+    let mut values = vec![
+        (
+            Heuristic::Manhattan.name(),
+            board.heuristic(Heuristic::Manhattan),
+        ),
+        (
+            Heuristic::LinearConflict.name(),
+            board.heuristic(Heuristic::LinearConflict),
+        ),
+    ];
+    if let Some(database) = PatternDatabase::build(board) {
+        values.push(("Pattern database", database.evaluate(board)));
+    }
+    let estimates = values
+        .into_iter()
+        .map(|(name, value)| HeuristicEstimate {
+            name,
+            value,
+            gap: (optimal_length as u32).saturating_sub(value),
+        })
+        .collect();
+
+    Some(Rating {
+        optimal_length,
+        estimates,
+        nodes_expanded: solution.stats.nodes_expanded,
+        difficulty: Difficulty::of_length(optimal_length, board.get_size()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::InstanceSet;
+
+    #[test]
+    fn test_rating_of_the_hardest_8_puzzle() {
+        let board = Board::new(vec![vec![8, 6, 7], vec![2, 5, 4], vec![3, 0, 1]]);
+        let rating = rate(&board).unwrap();
+        assert_eq!(rating.optimal_length, 31);
+        assert_eq!(rating.difficulty, Difficulty::Expert);
+        assert!(rating.nodes_expanded > 0);
+
+        let values: Vec<u32> = rating.estimates.iter().map(|e| e.value).collect();
+        assert_eq!(values.len(), 3);
+        // Each estimate is admissible and at least as strong as Manhattan
+        assert!(values
+            .iter()
+            .all(|&value| value >= values[0] && value <= 31));
+        assert!(rating
+            .estimates
+            .iter()
+            .all(|estimate| estimate.gap == 31 - estimate.value));
+    }
+
+    #[test]
+    fn test_pattern_database_bounds_korf_instances() {
+        let instances = InstanceSet::Korf100.instances();
+        let database = PatternDatabase::build(&instances[0].board).unwrap();
+        for instance in &instances {
+            let value = database.evaluate(&instance.board);
+            assert!(value as usize <= instance.optimal_length.unwrap());
+            assert!(value >= instance.board.heuristic(Heuristic::Manhattan));
+        }
+    }
+
+    #[test]
+    fn test_difficulty_buckets() {
+        assert_eq!(
+            rate(&Board::new(Board::standard_goal(3)))
+                .unwrap()
+                .difficulty,
+            Difficulty::Trivial
+        );
+        assert_eq!(Difficulty::of_length(12, 3), Difficulty::Easy);
+        assert_eq!(Difficulty::of_length(13, 3), Difficulty::Medium);
+        assert_eq!(Difficulty::of_length(50, 4), Difficulty::Hard);
+        assert!(rate(&Board::new(vec![
+            vec![2, 1, 3],
+            vec![4, 5, 6],
+            vec![7, 8, 0]
+        ]))
+        .is_none());
+    }
+}
@@ -0,0 +1,219 @@
+use crate::common::{Board, Direction, Heuristic, MovePruner, MovePruning, SearchStats};
+use crate::{SolutionInfo, Solver};
+
+// Node on the current RBFS path
+#[derive(Clone)]
+struct Node {
+    board: Board,
+    last_move: Option<Direction>,
+    pruner_state: u32,
+    g_cost: u32,
+    f_cost: u32,    // Static f = g + h
+    backed_up: u32, // Best f known below this node, raised when its subtree is forgotten
+}
+
+enum Outcome {
+    Found,
+    Exceeded(u32),
+}
+
+pub struct RBFSSolver {
+    initial_board: Board,
+    heuristic: Heuristic,
+    pruning: MovePruning,
+}
+
+impl RBFSSolver {
+    pub fn with_heuristic(mut self, heuristic: Heuristic) -> Self {
+        self.heuristic = heuristic;
+        self
+    }
+
+    pub fn with_pruning(mut self, pruning: MovePruning) -> Self {
+        self.pruning = pruning;
+        self
+    }
+
+    fn child(&self, parent: &Node, dir: Direction, pruner_state: u32) -> Option<Node> {
+        let mut board = parent.board.clone();
+        board.make_move(dir).ok()?;
+
+        let g_cost = parent.g_cost + 1;
+        let f_cost = g_cost + board.heuristic(self.heuristic);
+        // A parent whose backed-up value exceeds its static f was expanded before,
+        // so its children inherit that value instead of starting from scratch.
+        let backed_up = if parent.backed_up > parent.f_cost {
+            f_cost.max(parent.backed_up)
+        } else {
+            f_cost
+        };
+
+        Some(Node {
+            board,
+            last_move: Some(dir),
+            pruner_state,
+            g_cost,
+            f_cost,
+            backed_up,
+        })
+    }
+
+    fn search(
+        &self,
+        node: &Node,
+        bound: u32,
+        pruner: &MovePruner,
+        path: &mut Vec<Direction>,
+        stats: &mut SearchStats,
+    ) -> Outcome {
+        if node.board.is_goal() {
+            return Outcome::Found;
+        }
+
+        stats.nodes_expanded += 1;
+        if node.backed_up > node.f_cost {
+            stats.re_expansions += 1;
+        }
+
+        let mut children = Vec::new();
+        for dir in node.board.get_possible_moves() {
+            match pruner.step(node.pruner_state, dir) {
+                Some(pruner_state) => children.extend(self.child(node, dir, pruner_state)),
+                None => stats.pruned_moves += 1,
+            }
+        }
+        stats.nodes_generated += children.len();
+        stats.max_frontier = stats.max_frontier.max(path.len() + children.len());
+
+        if children.is_empty() {
+            return Outcome::Exceeded(u32::MAX);
+        }
+
+        loop {
+            children.sort_by_key(|child| child.backed_up);
+            let best = &children[0];
+            if best.backed_up > bound {
+                return Outcome::Exceeded(best.backed_up);
+            }
+            let alternative = children.get(1).map_or(u32::MAX, |child| child.backed_up);
+
+            path.push(best.last_move.expect("children always record their move"));
+            match self.search(best, bound.min(alternative), pruner, path, stats) {
+                Outcome::Found => return Outcome::Found,
+                Outcome::Exceeded(f_cost) => {
+                    children[0].backed_up = f_cost;
+                    path.pop();
+                }
+            }
+        }
+    }
+}
+
+impl Solver for RBFSSolver {
+    fn new(initial: Board) -> Self {
+        RBFSSolver {
+            initial_board: initial,
+            heuristic: Heuristic::default(),
+            pruning: MovePruning::default(),
+        }
+    }
+
+    fn new_with_goal(initial: Board) -> Self {
+        RBFSSolver {
+            initial_board: initial,
+            heuristic: Heuristic::default(),
+            pruning: MovePruning::default(),
+        }
+    }
+
+    fn solve(&self, optimal_length: Option<usize>) -> Option<SolutionInfo> {
+        // RBFS never terminates on an unreachable goal, so rule those out first
+        if !self.initial_board.is_solvable() {
+            return None;
+        }
+
+        let h_cost = self.initial_board.heuristic(self.heuristic);
+        let pruner = MovePruner::for_board(self.pruning, &self.initial_board);
+        let root = Node {
+            board: self.initial_board.clone(),
+            last_move: None,
+            pruner_state: pruner.start(),
+            g_cost: 0,
+            f_cost: h_cost,
+            backed_up: h_cost,
+        };
+
+        let mut path = Vec::new();
+        let mut stats = SearchStats::default();
+        match self.search(&root, u32::MAX, &pruner, &mut path, &mut stats) {
+            Outcome::Found => {
+                let cost = self.initial_board.path_cost(&path);
+                let goal = self.initial_board.goal_reached_by(&path);
+                Some(
+                    SolutionInfo::new(path, optimal_length)
+                        .with_cost(cost)
+                        .with_goal(goal)
+                        .with_stats(stats),
+                )
+            }
+            Outcome::Exceeded(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AStarSolver;
+
+    #[test]
+    fn test_already_solved() {
+        let board = Board::new(vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 0]]);
+        let solution = RBFSSolver::new(board).solve(None).unwrap();
+
+        assert!(solution.moves.is_empty());
+        assert_eq!(solution.stats.nodes_expanded, 0);
+    }
+
+    #[test]
+    fn test_matches_astar_length() {
+        let initial_state = vec![vec![8, 6, 7], vec![2, 5, 4], vec![3, 0, 1]];
+        let board = Board::new(initial_state);
+        let astar = AStarSolver::new(board.clone()).solve(None).unwrap();
+
+        for heuristic in [Heuristic::Manhattan, Heuristic::LinearConflict] {
+            let solution = RBFSSolver::new(board.clone())
+                .with_heuristic(heuristic)
+                .solve(None)
+                .unwrap();
+            assert_eq!(solution.moves.len(), astar.moves.len());
+            assert!(solution.stats.re_expansions > 0);
+
+            let mut test_board = board.clone();
+            for dir in solution.moves {
+                test_board.make_move(dir).unwrap();
+            }
+            assert!(test_board.is_goal());
+        }
+    }
+
+    #[test]
+    fn test_unsolvable_returns_none() {
+        let board = Board::new(vec![vec![2, 1, 3], vec![4, 5, 6], vec![7, 8, 0]]);
+        assert!(RBFSSolver::new(board).solve(None).is_none());
+    }
+
+    #[test]
+    fn test_pruning_keeps_optimal_length() {
+        let board = Board::new(vec![vec![8, 6, 7], vec![2, 5, 4], vec![3, 0, 1]]);
+        let inverse = RBFSSolver::new(board.clone()).solve(None).unwrap();
+        let automaton = RBFSSolver::new(board)
+            .with_pruning(MovePruning::Automaton)
+            .solve(None)
+            .unwrap();
+
+        assert_eq!(automaton.moves.len(), inverse.moves.len());
+        assert!(inverse.stats.pruned_moves > 0);
+        assert!(automaton.stats.nodes_expanded < inverse.stats.nodes_expanded);
+    }
+}
@@ -0,0 +1,369 @@
+use crate::common::{Board, Direction, Tile};
+use crate::{IDAStarSolver, SolutionInfo, Solver};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+
+// Side length of the block that is finished optimally
+const FINAL_BLOCK: usize = 3;
+
+// Direction the blank moves to go from `from` to the adjacent cell `to`
+fn direction_between(from: usize, to: usize, size: usize) -> Direction {
+    if to + size == from {
+        Direction::Up
+    } else if to == from + size {
+        Direction::Down
+    } else if to + 1 == from {
+        Direction::Left
+    } else {
+        Direction::Right
+    }
+}
+
+// Working state while peeling rows and columns off the board
+struct Reducer {
+    board: Board,
+    goal: Vec<Tile>,
+    size: usize,
+    locked: Vec<bool>,
+    moves: Vec<Direction>,
+}
+
+impl Reducer {
+    fn position_of(&self, tile: Tile) -> usize {
+        self.board
+            .tiles()
+            .iter()
+            .position(|&t| t == tile)
+            .expect("every tile is on the board")
+    }
+
+    fn neighbours(&self, pos: usize) -> impl Iterator<Item = usize> {
+        let size = self.size;
+        let (row, col) = (pos / size, pos % size);
+        [
+            (row > 0).then(|| pos - size),
+            (row + 1 < size).then(|| pos + size),
+            (col > 0).then(|| pos - 1),
+            (col + 1 < size).then(|| pos + 1),
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    // Shortest route over unlocked cells, excluding `from`, never entering `avoid`
+    fn route(&self, from: usize, to: usize, avoid: &[usize]) -> Option<Vec<usize>> {
+        let mut came_from = vec![usize::MAX; self.locked.len()];
+        let mut queue = VecDeque::from([from]);
+        came_from[from] = from;
+
+        while let Some(pos) = queue.pop_front() {
+            if pos == to {
+                let mut path = vec![to];
+                let mut cell = to;
+                while came_from[cell] != from {
+                    cell = came_from[cell];
+                    path.push(cell);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for next in self.neighbours(pos) {
+                if came_from[next] == usize::MAX && !self.locked[next] && !avoid.contains(&next) {
+                    came_from[next] = pos;
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    fn step_blank(&mut self, to: usize) {
+        let dir = direction_between(self.board.blank_pos(), to, self.size);
+        self.board.make_move(dir).expect("neighbouring cell");
+        self.moves.push(dir);
+    }
+
+    fn move_blank_to(&mut self, target: usize, avoid: &[usize]) -> Option<()> {
+        if self.board.blank_pos() == target {
+            return Some(());
+        }
+        for cell in self.route(self.board.blank_pos(), target, avoid)? {
+            self.step_blank(cell);
+        }
+        Some(())
+    }
+
+    // Walks a tile to `target` one cell at a time, fetching the blank in front of it
+    fn move_tile_to(&mut self, tile: Tile, target: usize) -> Option<()> {
+        loop {
+            let pos = self.position_of(tile);
+            if pos == target {
+                return Some(());
+            }
+            let next = self.route(pos, target, &[])?[0];
+            self.move_blank_to(next, &[pos])?;
+            self.step_blank(pos);
+        }
+    }
+
+    // Places the goal tiles of `cells` (a whole edge row or column, in order),
+    // where `inward` is the offset from an edge cell to its neighbour in the region
+    fn fix_line(&mut self, cells: &[usize], inward: isize) -> Option<()> {
+        let len = cells.len();
+        for &cell in &cells[..len - 2] {
+            self.move_tile_to(self.goal[cell], cell)?;
+            self.locked[cell] = true;
+        }
+
+        // The last two tiles cannot be placed one by one: bring both into the
+        // 2x3 patch at the end of the line and shuffle them in together
+        let (p, q) = (cells[len - 2], cells[len - 1]);
+        let (a, b) = (self.goal[p], self.goal[q]);
+        let patch: Vec<usize> = (0..3)
+            .flat_map(|depth| [p, q].map(|cell| (cell as isize + depth * inward) as usize))
+            .collect();
+
+        self.move_tile_to(a, p)?;
+        self.locked[p] = true;
+        // With p locked the blank would be cornered in q
+        if self.board.blank_pos() == q {
+            self.step_blank(patch[3]);
+        }
+        if !patch.contains(&self.position_of(b)) {
+            self.move_tile_to(b, patch[3])?;
+        }
+        self.locked[p] = false;
+
+        let occupied = [self.position_of(a), self.position_of(b)];
+        if !patch.contains(&self.board.blank_pos()) {
+            let entry = patch
+                .iter()
+                .filter(|cell| !occupied.contains(cell))
+                .find_map(|&cell| self.route(self.board.blank_pos(), cell, &occupied))?;
+            for cell in entry {
+                self.step_blank(cell);
+            }
+        }
+        self.arrange(&patch, [(a, p), (b, q)])?;
+
+        self.locked[p] = true;
+        self.locked[q] = true;
+        Some(())
+    }
+
+    // Moves the blank around inside `patch` until both tiles sit on their targets
+    fn arrange(&mut self, patch: &[usize], targets: [(Tile, usize); 2]) -> Option<()> {
+        let [(a, a_goal), (b, b_goal)] = targets;
+        let start = (
+            self.board.blank_pos(),
+            self.position_of(a),
+            self.position_of(b),
+        );
+        let mut came_from = HashMap::from([(start, start)]);
+        let mut queue = VecDeque::from([start]);
+
+        while let Some(state) = queue.pop_front() {
+            let (blank, pos_a, pos_b) = state;
+            if pos_a == a_goal && pos_b == b_goal {
+                let mut blanks = Vec::new();
+                let mut current = state;
+                while current != start {
+                    blanks.push(current.0);
+                    current = came_from[&current];
+                }
+                for &cell in blanks.iter().rev() {
+                    self.step_blank(cell);
+                }
+                return Some(());
+            }
+            for next in self
+                .neighbours(blank)
+                .filter(|cell| patch.contains(cell) && !self.locked[*cell])
+            {
+                // Whatever tile sat on `next` slides into the old blank cell
+                let swap = |pos: usize| if pos == next { blank } else { pos };
+                let next_state = (next, swap(pos_a), swap(pos_b));
+                if let Entry::Vacant(entry) = came_from.entry(next_state) {
+                    entry.insert(state);
+                    queue.push_back(next_state);
+                }
+            }
+        }
+        None
+    }
+
+    // Solves the last block optimally as a small puzzle of its own
+    fn finish_block(&mut self, top: usize, left: usize) -> Option<()> {
+        let size = self.size;
+        let cells: Vec<usize> = (0..FINAL_BLOCK)
+            .flat_map(|row| (0..FINAL_BLOCK).map(move |col| (top + row) * size + left + col))
+            .collect();
+
+        // Relabel the block's tiles 0..9 so it forms a regular 3x3 board
+        let mut labels: Vec<Tile> = cells.iter().map(|&cell| self.goal[cell]).collect();
+        labels.sort_unstable();
+        let label = |tile: Tile| labels.binary_search(&tile).expect("tile belongs to block") as i32;
+        let grid = |values: &[Tile]| -> Vec<Vec<i32>> {
+            cells
+                .chunks(FINAL_BLOCK)
+                .map(|row| row.iter().map(|&cell| label(values[cell])).collect())
+                .collect()
+        };
+
+        let mask = cells
+            .chunks(FINAL_BLOCK)
+            .map(|row| {
+                row.iter()
+                    .map(|&cell| self.board.is_blocked(cell))
+                    .collect()
+            })
+            .collect();
+        let block = Board::new_with_goal(grid(self.board.tiles()), grid(&self.goal))
+            .with_blocked_cells(mask);
+        let solution = IDAStarSolver::new(block).solve(None)?;
+        for dir in solution.moves {
+            self.board.make_move(dir).ok()?;
+            self.moves.push(dir);
+        }
+        Some(())
+    }
+
+    fn solve(&mut self) -> Option<()> {
+        let size = self.size;
+        let goal_blank = self.goal.iter().position(|&t| t == 0)?;
+        let (blank_row, blank_col) = (goal_blank / size, goal_blank % size);
+        let (mut top, mut bottom, mut left, mut right) = (0, size, 0, size);
+
+        // Peel the side that does not hold the goal blank, keeping the region square-ish
+        while bottom - top > FINAL_BLOCK || right - left > FINAL_BLOCK {
+            if bottom - top >= right - left {
+                let row = if blank_row != top { top } else { bottom - 1 };
+                let cells: Vec<usize> = (left..right).map(|col| row * size + col).collect();
+                let inward = if row == top {
+                    size as isize
+                } else {
+                    -(size as isize)
+                };
+                self.fix_line(&cells, inward)?;
+                if row == top {
+                    top += 1;
+                } else {
+                    bottom -= 1;
+                }
+            } else {
+                let col = if blank_col != left { left } else { right - 1 };
+                let cells: Vec<usize> = (top..bottom).map(|row| row * size + col).collect();
+                let inward = if col == left { 1 } else { -1 };
+                self.fix_line(&cells, inward)?;
+                if col == left {
+                    left += 1;
+                } else {
+                    right -= 1;
+                }
+            }
+        }
+
+        self.finish_block(top, left)
+    }
+}
+
+/// Constructive solver for large boards: fixes one edge row or column at a
+/// time and shrinks the puzzle until only a 3x3 block is left, which is then
+/// solved optimally. Fast for any size, but the solution is not optimal.
+/// Blocked cells are treated as already placed; layouts where they wall off
+/// a pocket the strategy needs to pass through yield `None`.
+pub struct ReductionSolver {
+    initial_board: Board,
+}
+
+impl Solver for ReductionSolver {
+    fn new(initial: Board) -> Self {
+        ReductionSolver {
+            initial_board: initial,
+        }
+    }
+
+    fn new_with_goal(initial: Board) -> Self {
+        ReductionSolver {
+            initial_board: initial,
+        }
+    }
+
+    fn solve(&self, optimal_length: Option<usize>) -> Option<SolutionInfo> {
+        let size = self.initial_board.get_size();
+        if size <= FINAL_BLOCK {
+            return IDAStarSolver::new(self.initial_board.clone()).solve(optimal_length);
+        }
+
+        // Wildcards are pinned down to one concrete arrangement up front
+        let mut reducer = Reducer {
+            board: self.initial_board.clone(),
+            goal: self.initial_board.reachable_goal()?,
+            size,
+            // Blocked cells start out locked
+            locked: (0..size * size)
+                .map(|pos| self.initial_board.is_blocked(pos))
+                .collect(),
+            moves: Vec::new(),
+        };
+        reducer.solve()?;
+
+        let cost = self.initial_board.path_cost(&reducer.moves);
+        let goal = self.initial_board.goal_reached_by(&reducer.moves);
+        Some(
+            SolutionInfo::new(reducer.moves, optimal_length)
+                .with_cost(cost)
+                .with_goal(goal),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::input::parse_moves;
+    use crate::PuzzleGenerator;
+
+    fn assert_solves(board: Board) {
+        let solution = ReductionSolver::new(board.clone()).solve(None).unwrap();
+        let mut test_board = board;
+        for dir in solution.moves {
+            test_board.make_move(dir).unwrap();
+        }
+        assert!(test_board.is_goal());
+    }
+
+    #[test]
+    fn test_solves_various_sizes() {
+        for (size, seed) in [(4, 1), (5, 2), (8, 3), (20, 4)] {
+            let mut generator = PuzzleGenerator::new(size).with_seed(seed);
+            assert_solves(generator.random_walk(size * size * 20));
+        }
+    }
+
+    #[test]
+    fn test_goal_with_blank_in_top_left() {
+        let goal: Vec<Vec<i32>> = (0..5)
+            .map(|row| (0..5).map(|col| row * 5 + col).collect())
+            .collect();
+        let mut board = Board::new_with_goal(goal.clone(), goal);
+        // A snake through the rows, then one through the columns, both
+        // ending back in the top-left corner
+        let walk = parse_moves("RRRRDLLLLDRRRRDLLLLDRRRRUUUULLLL DDDDRUUUURDDDDRUUUURDDDDLLLLUUUU")
+            .unwrap();
+        for _ in 0..5 {
+            for &dir in &walk {
+                board.make_move(dir).unwrap();
+            }
+        }
+        assert!(!board.is_goal());
+        assert_solves(board);
+    }
+
+    #[test]
+    fn test_unsolvable_returns_none() {
+        let mut rows = Board::standard_goal(4);
+        rows[0].swap(0, 1);
+        assert!(ReductionSolver::new(Board::new(rows)).solve(None).is_none());
+    }
+}
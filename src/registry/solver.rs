@@ -0,0 +1,209 @@
+use crate::common::{Board, Heuristic, MovePruning, SearchLimits};
+use crate::{
+    AStarSolver, BFSSolver, DFSSolver, DistanceTable, FringeSolver, HDAStarSolver, IDAStarSolver,
+    ParallelIDAStarSolver, RBFSSolver, ReductionSolver, SMAStarSolver, SolutionEnumerator,
+    SolutionInfo, Solver, TableSolver, UniformCostSolver, YenSolver,
+};
+
+/// Settings shared by every solver run through `SolverKind`. Solvers that
+/// have no use for a setting ignore it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SolverOptions {
+    pub heuristic: Heuristic,
+    pub pruning: MovePruning,
+    pub limits: SearchLimits,
+    /// Let solvers that can narrate their search print it as they go
+    pub trace: bool,
+}
+
+/// Every solver in the crate, selectable by name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SolverKind {
+    Dfs,
+    Bfs,
+    AStar,
+    UniformCost,
+    IdaStar,
+    ParallelIdaStar,
+    HdaStar,
+    Rbfs,
+    Fringe,
+    SmaStar,
+    Reduction,
+    Table,
+    Enumeration,
+    Yen,
+}
+
+impl SolverKind {
+    pub const ALL: [SolverKind; 14] = [
+        SolverKind::Dfs,
+        SolverKind::Bfs,
+        SolverKind::AStar,
+        SolverKind::UniformCost,
+        SolverKind::IdaStar,
+        SolverKind::ParallelIdaStar,
+        SolverKind::HdaStar,
+        SolverKind::Rbfs,
+        SolverKind::Fringe,
+        SolverKind::SmaStar,
+        SolverKind::Reduction,
+        SolverKind::Table,
+        SolverKind::Enumeration,
+        SolverKind::Yen,
+    ];
+
+    /// Short name used on the command line.
+    pub fn name(&self) -> &'static str {
+        match self {
+            SolverKind::Dfs => "dfs",
+            SolverKind::Bfs => "bfs",
+            SolverKind::AStar => "astar",
+            SolverKind::UniformCost => "ucs",
+            SolverKind::IdaStar => "ida",
+            SolverKind::ParallelIdaStar => "ida-parallel",
+            SolverKind::HdaStar => "hda",
+            SolverKind::Rbfs => "rbfs",
+            SolverKind::Fringe => "fringe",
+            SolverKind::SmaStar => "sma",
+            SolverKind::Reduction => "reduction",
+            SolverKind::Table => "table",
+            SolverKind::Enumeration => "enumeration",
+            SolverKind::Yen => "yen",
+        }
+    }
+
+    /// Name shown in tables and visualizations.
+    pub fn label(&self) -> &'static str {
+        match self {
+            SolverKind::Dfs => "DFS",
+            SolverKind::Bfs => "BFS",
+            SolverKind::AStar => "A*",
+            SolverKind::UniformCost => "UCS",
+            SolverKind::IdaStar => "IDA*",
+            SolverKind::ParallelIdaStar => "Parallel IDA*",
+            SolverKind::HdaStar => "HDA*",
+            SolverKind::Rbfs => "RBFS",
+            SolverKind::Fringe => "Fringe",
+            SolverKind::SmaStar => "SMA*",
+            SolverKind::Reduction => "Reduction",
+            SolverKind::Table => "Table",
+            SolverKind::Enumeration => "Enumeration",
+            SolverKind::Yen => "Yen",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<SolverKind> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+
+    /// Whether `SolverOptions::heuristic` changes how this solver searches.
+    pub fn uses_heuristic(&self) -> bool {
+        matches!(
+            self,
+            SolverKind::AStar
+                | SolverKind::IdaStar
+                | SolverKind::ParallelIdaStar
+                | SolverKind::HdaStar
+                | SolverKind::Rbfs
+                | SolverKind::Fringe
+                | SolverKind::SmaStar
+                | SolverKind::Enumeration
+                | SolverKind::Yen
+        )
+    }
+
+    /// Whether `SolverOptions::limits` can stop this solver early.
+    pub fn supports_limits(&self) -> bool {
+        matches!(
+            self,
+            SolverKind::UniformCost
+                | SolverKind::IdaStar
+                | SolverKind::ParallelIdaStar
+                | SolverKind::Fringe
+        )
+    }
+
+    pub fn solve(&self, board: &Board, options: &SolverOptions) -> Option<SolutionInfo> {
+        let board = board.clone();
+        let SolverOptions {
+            heuristic,
+            pruning,
+            limits,
+            trace,
+        } = options.clone();
+
+        match self {
+            SolverKind::Dfs => DFSSolver::new(board).with_pruning(pruning).solve(None),
+            SolverKind::Bfs => BFSSolver::new(board).with_trace(trace).solve(None),
+            SolverKind::AStar => AStarSolver::new(board)
+                .with_heuristic(heuristic)
+                .solve(None),
+            SolverKind::UniformCost => UniformCostSolver::new(board)
+                .with_limits(limits)
+                .solve(None),
+            SolverKind::IdaStar => IDAStarSolver::new(board)
+                .with_heuristic(heuristic)
+                .with_pruning(pruning)
+                .with_limits(limits)
+                .solve(None),
+            SolverKind::ParallelIdaStar => ParallelIDAStarSolver::new(board)
+                .with_heuristic(heuristic)
+                .with_pruning(pruning)
+                .with_limits(limits)
+                .solve(None),
+            SolverKind::HdaStar => HDAStarSolver::new(board)
+                .with_heuristic(heuristic)
+                .solve(None),
+            SolverKind::Rbfs => RBFSSolver::new(board)
+                .with_heuristic(heuristic)
+                .with_pruning(pruning)
+                .solve(None),
+            SolverKind::Fringe => FringeSolver::new(board)
+                .with_heuristic(heuristic)
+                .with_limits(limits)
+                .solve(None),
+            SolverKind::SmaStar => SMAStarSolver::new(board)
+                .with_heuristic(heuristic)
+                .solve(None),
+            SolverKind::Reduction => ReductionSolver::new(board).solve(None),
+            SolverKind::Table if board.get_size() == 3 => {
+                let table = DistanceTable::shared(&board);
+                TableSolver::new(board).with_table(table).solve(None)
+            }
+            SolverKind::Table => None,
+            SolverKind::Enumeration => SolutionEnumerator::new(board)
+                .with_heuristic(heuristic)
+                .solve(None),
+            SolverKind::Yen => YenSolver::new(board).with_heuristic(heuristic).solve(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_round_trip() {
+        for kind in SolverKind::ALL {
+            assert_eq!(SolverKind::from_name(kind.name()), Some(kind));
+        }
+        assert_eq!(SolverKind::from_name("quantum"), None);
+    }
+
+    #[test]
+    fn test_every_solver_solves_a_short_puzzle() {
+        let board = Board::new(vec![vec![1, 2, 3], vec![4, 0, 6], vec![7, 5, 8]]);
+        let options = SolverOptions::default();
+        for kind in SolverKind::ALL {
+            let solution = kind.solve(&board, &options).unwrap();
+            assert_eq!(
+                board.goal_reached_by(&solution.moves),
+                Some(0),
+                "{}",
+                kind.name()
+            );
+        }
+    }
+}
@@ -0,0 +1,2 @@
+mod shortener;
+pub use shortener::{PathShortener, ShorteningReport};
@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+
+use crate::common::{verify, Board, Direction, Heuristic, Tile, VerifyError};
+use crate::{IDAStarSolver, SolutionInfo, Solver};
+
+/// How much shortening a solution saved, and where.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ShorteningReport {
+    pub original_length: usize,
+    pub original_cost: u32,
+    /// Stretches of the path that came back to a state it had already visited
+    pub cycles_removed: usize,
+    /// Moves dropped with those cycles
+    pub cycle_moves: usize,
+    /// Windows replaced by a cheaper optimal sub-path
+    pub windows_replaced: usize,
+    pub final_length: usize,
+    pub final_cost: u32,
+}
+
+impl ShorteningReport {
+    pub fn moves_saved(&self) -> usize {
+        self.original_length - self.final_length
+    }
+
+    pub fn cost_saved(&self) -> u32 {
+        self.original_cost - self.final_cost
+    }
+}
+
+/// Shortens a valid solution without changing where it starts or ends.
+/// Cycles go first: whenever the path returns to a state it visited, the
+/// moves in between are dropped. Then every window of `window` moves is
+/// replaced by a cheapest path between its two ends whenever that path is
+/// neither longer nor more expensive, and better on one count, until a
+/// pass over the path finds nothing left to improve. With move costs a
+/// cheapest sub-path that takes more moves is passed over, so the result
+/// need not be the cheapest solution. Windows overlap by half their length
+/// so that detours straddling a window boundary are still found.
+pub struct PathShortener {
+    board: Board,
+    window: usize,
+}
+
+impl PathShortener {
+    pub fn new(board: Board) -> Self {
+        PathShortener { board, window: 12 }
+    }
+
+    /// Longer windows find more savings; each one is an IDA* search.
+    pub fn with_window(mut self, window: usize) -> Self {
+        self.window = window.max(2);
+        self
+    }
+
+    fn states(&self, moves: &[Direction]) -> Vec<Board> {
+        let mut board = self.board.clone();
+        let mut states = Vec::with_capacity(moves.len() + 1);
+        states.push(board.clone());
+        for &dir in moves {
+            board.make_move(dir).unwrap();
+            states.push(board.clone());
+        }
+        states
+    }
+
+    // Drops every cycle; returns how many and how many moves they held
+    fn remove_cycles(&self, moves: &mut Vec<Direction>) -> (usize, usize) {
+        let mut board = self.board.clone();
+        let mut seen: HashMap<Vec<Tile>, usize> = HashMap::from([(board.tiles().to_vec(), 0)]);
+        let mut kept: Vec<Direction> = Vec::with_capacity(moves.len());
+        let mut states = vec![board.tiles().to_vec()];
+        let (mut cycles, mut dropped) = (0, 0);
+
+        for &dir in moves.iter() {
+            board.make_move(dir).unwrap();
+            kept.push(dir);
+            match seen.get(board.tiles()) {
+                Some(&index) => {
+                    cycles += 1;
+                    dropped += kept.len() - index;
+                    kept.truncate(index);
+                    for state in states.drain(index + 1..) {
+                        seen.remove(&state);
+                    }
+                }
+                None => {
+                    seen.insert(board.tiles().to_vec(), kept.len());
+                    states.push(board.tiles().to_vec());
+                }
+            }
+        }
+        *moves = kept;
+        (cycles, dropped)
+    }
+
+    // Cheapest path from `from` to `to` under the board's costs and walls
+    fn optimal_segment(&self, from: &Board, to: &Board) -> Option<Vec<Direction>> {
+        let size = self.board.get_size();
+        let grid = |board: &Board| -> Vec<Vec<i32>> {
+            board
+                .get_state()
+                .into_iter()
+                .map(|row| row.into_iter().map(i32::from).collect())
+                .collect()
+        };
+        let mask: Vec<Vec<bool>> = (0..size)
+            .map(|row| {
+                (0..size)
+                    .map(|col| self.board.is_blocked(row * size + col))
+                    .collect()
+            })
+            .collect();
+        let segment = Board::new_with_goal(grid(from), grid(to))
+            .with_move_cost(self.board.get_move_cost().clone())
+            .with_blocked_cells(mask);
+        IDAStarSolver::new(segment)
+            .with_heuristic(Heuristic::LinearConflict)
+            .solve(None)
+            .map(|solution| solution.moves)
+    }
+
+    /// A solution with the same start and end as `solution`, no longer and
+    /// no more expensive, with a report of the savings. Fails if the moves
+    /// do not solve the board.
+    pub fn shorten(
+        &self,
+        solution: &SolutionInfo,
+    ) -> Result<(SolutionInfo, ShorteningReport), VerifyError> {
+        verify(&self.board, self.board.goal_states(), &solution.moves)?;
+        let mut report = ShorteningReport {
+            original_length: solution.moves.len(),
+            original_cost: self.board.path_cost(&solution.moves),
+            ..ShorteningReport::default()
+        };
+
+        let mut moves = solution.moves.clone();
+        let (cycles, dropped) = self.remove_cycles(&mut moves);
+        report.cycles_removed = cycles;
+        report.cycle_moves = dropped;
+
+        let stride = (self.window / 2).max(1);
+        let mut improved = true;
+        while improved {
+            improved = false;
+            let mut start = 0;
+            while start + 1 < moves.len() {
+                let end = (start + self.window).min(moves.len());
+                let states = self.states(&moves[..end]);
+                let current = &moves[start..end];
+                if let Some(better) = self.optimal_segment(&states[start], &states[end]) {
+                    let cost = |path: &[Direction]| states[start].path_cost(path);
+                    let (new, old) = (
+                        (cost(&better), better.len()),
+                        (cost(current), current.len()),
+                    );
+                    if new.0 <= old.0 && new.1 <= old.1 && new != old {
+                        moves.splice(start..end, better);
+                        report.windows_replaced += 1;
+                        improved = true;
+                    }
+                }
+                start += stride;
+            }
+            // Spliced sub-paths can meet states from elsewhere on the path
+            let (cycles, dropped) = self.remove_cycles(&mut moves);
+            report.cycles_removed += cycles;
+            report.cycle_moves += dropped;
+        }
+
+        report.final_length = moves.len();
+        report.final_cost = self.board.path_cost(&moves);
+        let shortened = SolutionInfo::new(moves, solution.optimal_length)
+            .with_cost(report.final_cost)
+            .with_goal(self.board.goal_reached_by(&solution.moves))
+            .with_stats(solution.stats.clone());
+        Ok((shortened, report))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::input::parse_moves;
+    use crate::{DFSSolver, MoveCost, UniformCostSolver};
+
+    fn board() -> Board {
+        Board::new(vec![vec![1, 2, 3], vec![4, 0, 6], vec![7, 5, 8]])
+    }
+
+    #[test]
+    fn test_dfs_solution_becomes_optimal() {
+        let board = board();
+        let solution = DFSSolver::new(board.clone()).solve(None).unwrap();
+        assert!(solution.moves.len() > 2);
+
+        let (shortened, report) = PathShortener::new(board.clone())
+            .shorten(&solution)
+            .unwrap();
+        assert_eq!(shortened.moves, parse_moves("DR").unwrap());
+        assert_eq!(report.final_length, 2);
+        assert_eq!(report.moves_saved(), solution.moves.len() - 2);
+        assert_eq!(
+            verify(&board, board.goal_states(), &shortened.moves),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_cycles_are_cut_at_repeated_states() {
+        let board = board();
+        // Three laps of a 2x2 square restore it, then a step there and back
+        let moves = parse_moves("LURDLURDLURD LR DR").unwrap();
+        let shortener = PathShortener::new(board.clone());
+        let mut cut = moves.clone();
+        let (cycles, dropped) = shortener.remove_cycles(&mut cut);
+        assert_eq!(cut, parse_moves("DR").unwrap());
+        assert_eq!((cycles, dropped), (2, moves.len() - 2));
+
+        let solution = SolutionInfo::new(moves, None);
+        let (_, report) = shortener.shorten(&solution).unwrap();
+        assert_eq!(report.windows_replaced, 0);
+    }
+
+    #[test]
+    fn test_cost_never_rises() {
+        let board = Board::new(vec![vec![4, 1, 2], vec![5, 0, 3], vec![7, 8, 6]])
+            .with_move_cost(MoveCost::FaceValue);
+        let solution = DFSSolver::new(board.clone()).solve(None).unwrap();
+        let (shortened, report) = PathShortener::new(board.clone())
+            .with_window(8)
+            .shorten(&solution)
+            .unwrap();
+        assert!(report.final_cost <= report.original_cost);
+        assert_eq!(shortened.total_cost, report.final_cost);
+        assert_eq!(
+            verify(&board, board.goal_states(), &shortened.moves),
+            Ok(())
+        );
+
+        let broken = SolutionInfo::new(parse_moves("UU").unwrap(), None);
+        assert!(PathShortener::new(board).shorten(&broken).is_err());
+    }
+
+    #[test]
+    fn test_longer_cheapest_path_is_not_taken() {
+        let board = Board::new(vec![vec![1, 2, 3], vec![0, 5, 6], vec![7, 8, 4]])
+            .with_move_cost(MoveCost::FaceValue);
+        // The cheapest solution takes 15 moves where 11 would do
+        let cheapest = UniformCostSolver::new(board.clone()).solve(None).unwrap();
+        assert_eq!((cheapest.moves.len(), cheapest.total_cost), (15, 62));
+
+        let moves = parse_moves("DRRULDLURRD").unwrap();
+        let solution = SolutionInfo::new(moves, None).with_cost(64);
+        let (shortened, report) = PathShortener::new(board.clone())
+            .shorten(&solution)
+            .unwrap();
+        assert!(report.final_length <= report.original_length);
+        assert!(report.final_cost <= report.original_cost);
+        assert_eq!(report.moves_saved(), 11 - shortened.moves.len());
+        assert_eq!(report.cost_saved(), 64 - shortened.total_cost);
+        assert_eq!(
+            verify(&board, board.goal_states(), &shortened.moves),
+            Ok(())
+        );
+    }
+}
@@ -0,0 +1,334 @@
+use crate::common::{Board, Direction, Heuristic, SearchStats};
+use crate::{SolutionInfo, Solver};
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
+
+const DEFAULT_MAX_NODES: usize = 100_000;
+
+// Ordering key shared by the open list and the leaf list: (f, shallower last, id)
+type Key = (u32, Reverse<u32>, usize);
+
+struct Node {
+    board: Board,
+    parent: Option<usize>,
+    last_move: Option<Direction>,
+    g_cost: u32,
+    f_cost: u32,
+    children: Vec<usize>,
+    forgotten_f: u32, // Lowest f among children dropped from memory
+    expanded: bool,
+}
+
+impl Node {
+    // Leaves are ranked by their own f, partly forgotten nodes by what they lost
+    fn open_key(&self, id: usize) -> Option<Key> {
+        if self.children.is_empty() {
+            Some((self.f_cost, Reverse(self.g_cost), id))
+        } else if self.forgotten_f < u32::MAX {
+            Some((self.forgotten_f, Reverse(self.g_cost), id))
+        } else {
+            None
+        }
+    }
+}
+
+// Bookkeeping for a single bounded search
+struct Memory {
+    nodes: Vec<Option<Node>>,
+    free: Vec<usize>,
+    open: BTreeSet<Key>,
+    leaves: BTreeSet<Key>,
+}
+
+impl Memory {
+    fn len(&self) -> usize {
+        self.nodes.len() - self.free.len()
+    }
+
+    fn node(&self, id: usize) -> &Node {
+        self.nodes[id].as_ref().expect("node is in memory")
+    }
+
+    fn node_mut(&mut self, id: usize) -> &mut Node {
+        self.nodes[id].as_mut().expect("node is in memory")
+    }
+
+    fn insert(&mut self, node: Node) -> usize {
+        match self.free.pop() {
+            Some(id) => {
+                self.nodes[id] = Some(node);
+                id
+            }
+            None => {
+                self.nodes.push(Some(node));
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    // Take a node out of both lists before changing anything its keys depend on
+    fn detach(&mut self, id: usize) {
+        let node = self.node(id);
+        let open_key = node.open_key(id);
+        let leaf_key = (node.f_cost, Reverse(node.g_cost), id);
+        if let Some(key) = open_key {
+            self.open.remove(&key);
+        }
+        self.leaves.remove(&leaf_key);
+    }
+
+    fn attach(&mut self, id: usize) {
+        let node = self.node(id);
+        let open_key = node.open_key(id);
+        // The root is never forgotten, so it never needs to be a deletion candidate
+        let leaf_key = (node.children.is_empty() && node.parent.is_some()).then_some((
+            node.f_cost,
+            Reverse(node.g_cost),
+            id,
+        ));
+        if let Some(key) = open_key {
+            self.open.insert(key);
+        }
+        if let Some(key) = leaf_key {
+            self.leaves.insert(key);
+        }
+    }
+
+    // Drop the shallowest highest-f leaf, backing its f up into the parent
+    fn forget_worst_leaf(&mut self, current: usize) -> bool {
+        let Some(&(_, _, worst)) = self.leaves.iter().next_back() else {
+            return false;
+        };
+
+        self.detach(worst);
+        let removed = self.nodes[worst].take().expect("leaf is in memory");
+        self.free.push(worst);
+
+        let parent = removed.parent.expect("the root is never a leaf candidate");
+        if parent != current {
+            self.detach(parent);
+        }
+        let node = self.node_mut(parent);
+        node.children.retain(|&child| child != worst);
+        node.forgotten_f = node.forgotten_f.min(removed.f_cost);
+        if parent != current {
+            if node.children.is_empty() {
+                node.f_cost = node.forgotten_f;
+                node.forgotten_f = u32::MAX;
+            }
+            self.attach(parent);
+        }
+        true
+    }
+
+    fn path_to(&self, mut id: usize) -> Vec<Direction> {
+        let mut path = Vec::new();
+        while let Some(node) = self.nodes[id].as_ref() {
+            match (node.last_move, node.parent) {
+                (Some(dir), Some(parent)) => {
+                    path.push(dir);
+                    id = parent;
+                }
+                _ => break,
+            }
+        }
+        path.reverse();
+        path
+    }
+}
+
+pub struct SMAStarSolver {
+    initial_board: Board,
+    heuristic: Heuristic,
+    max_nodes: usize,
+}
+
+impl SMAStarSolver {
+    pub fn with_heuristic(mut self, heuristic: Heuristic) -> Self {
+        self.heuristic = heuristic;
+        self
+    }
+
+    /// Caps the number of nodes kept in memory at once. Solutions deeper than
+    /// `max_nodes - 1` moves cannot be represented and are not found.
+    pub fn with_max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = max_nodes.max(1);
+        self
+    }
+
+    fn expand(&self, memory: &mut Memory, id: usize, stats: &mut SearchStats) {
+        memory.detach(id);
+
+        let node = memory.node_mut(id);
+        if node.expanded {
+            stats.re_expansions += 1;
+        }
+        node.expanded = true;
+        // Regenerated children may not look better than what was backed up for them
+        let base_f = if node.children.is_empty() {
+            node.f_cost
+        } else {
+            node.forgotten_f
+        };
+        node.forgotten_f = u32::MAX;
+
+        let node = memory.node(id);
+        let board = node.board.clone();
+        let g_cost = node.g_cost + 1;
+        let last_move = node.last_move;
+        let present: Vec<Direction> = node
+            .children
+            .iter()
+            .filter_map(|&child| memory.node(child).last_move)
+            .collect();
+
+        for dir in board.get_possible_moves() {
+            if last_move == Some(dir.opposite()) || present.contains(&dir) {
+                continue;
+            }
+            let mut child_board = board.clone();
+            if child_board.make_move(dir).is_err() {
+                continue;
+            }
+            stats.nodes_generated += 1;
+
+            // A non-goal node on the last level that fits in memory can never lead anywhere
+            let f_cost = if !child_board.is_goal() && g_cost as usize + 1 >= self.max_nodes {
+                u32::MAX
+            } else {
+                (g_cost + child_board.heuristic(self.heuristic)).max(base_f)
+            };
+
+            if memory.len() >= self.max_nodes && !memory.forget_worst_leaf(id) {
+                let node = memory.node_mut(id);
+                node.forgotten_f = node.forgotten_f.min(f_cost);
+                continue;
+            }
+
+            let child = memory.insert(Node {
+                board: child_board,
+                parent: Some(id),
+                last_move: Some(dir),
+                g_cost,
+                f_cost,
+                children: Vec::new(),
+                forgotten_f: u32::MAX,
+                expanded: false,
+            });
+            memory.node_mut(id).children.push(child);
+            memory.attach(child);
+        }
+
+        let node = memory.node_mut(id);
+        if node.children.is_empty() {
+            // Dead end, or every child had to be dropped straight away
+            node.f_cost = node.forgotten_f;
+            node.forgotten_f = u32::MAX;
+        }
+        memory.attach(id);
+    }
+}
+
+impl Solver for SMAStarSolver {
+    fn new(initial: Board) -> Self {
+        SMAStarSolver {
+            initial_board: initial,
+            heuristic: Heuristic::default(),
+            max_nodes: DEFAULT_MAX_NODES,
+        }
+    }
+
+    fn new_with_goal(initial: Board) -> Self {
+        SMAStarSolver {
+            initial_board: initial,
+            heuristic: Heuristic::default(),
+            max_nodes: DEFAULT_MAX_NODES,
+        }
+    }
+
+    fn solve(&self, optimal_length: Option<usize>) -> Option<SolutionInfo> {
+        if !self.initial_board.is_solvable() {
+            return None;
+        }
+
+        let mut memory = Memory {
+            nodes: Vec::new(),
+            free: Vec::new(),
+            open: BTreeSet::new(),
+            leaves: BTreeSet::new(),
+        };
+        let root = memory.insert(Node {
+            board: self.initial_board.clone(),
+            parent: None,
+            last_move: None,
+            g_cost: 0,
+            f_cost: self.initial_board.heuristic(self.heuristic),
+            children: Vec::new(),
+            forgotten_f: u32::MAX,
+            expanded: false,
+        });
+        memory.attach(root);
+
+        let mut stats = SearchStats::default();
+        // Lowest f first, deepest among equals
+        while let Some(&(f_cost, _, id)) = memory.open.iter().next() {
+            if f_cost == u32::MAX {
+                break; // Nothing left that fits within the node cap
+            }
+
+            let node = memory.node(id);
+            if node.children.is_empty() && node.board.is_goal() {
+                let path = memory.path_to(id);
+                let cost = self.initial_board.path_cost(&path);
+                let goal = self.initial_board.goal_reached_by(&path);
+                return Some(
+                    SolutionInfo::new(path, optimal_length)
+                        .with_cost(cost)
+                        .with_goal(goal)
+                        .with_stats(stats),
+                );
+            }
+
+            stats.nodes_expanded += 1;
+            self.expand(&mut memory, id, &mut stats);
+            stats.max_frontier = stats.max_frontier.max(memory.open.len());
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AStarSolver;
+
+    #[test]
+    fn test_optimal_within_small_cap() {
+        let board = Board::new(vec![vec![8, 6, 7], vec![2, 5, 4], vec![3, 0, 1]]);
+        let astar = AStarSolver::new(board.clone()).solve(None).unwrap();
+
+        let solution = SMAStarSolver::new(board.clone())
+            .with_max_nodes(200)
+            .solve(None)
+            .unwrap();
+        assert_eq!(solution.moves.len(), astar.moves.len());
+        assert!(solution.stats.re_expansions > 0);
+
+        let mut test_board = board;
+        for dir in solution.moves {
+            test_board.make_move(dir).unwrap();
+        }
+        assert!(test_board.is_goal());
+    }
+
+    #[test]
+    fn test_cap_too_small_for_solution() {
+        let board = Board::new(vec![vec![1, 2, 3], vec![4, 0, 6], vec![7, 5, 8]]);
+        let solver = SMAStarSolver::new(board.clone()).with_max_nodes(2);
+        assert!(solver.solve(None).is_none());
+
+        let solution = SMAStarSolver::new(board).with_max_nodes(3).solve(None);
+        assert_eq!(solution.unwrap().moves.len(), 2);
+    }
+}
@@ -0,0 +1,364 @@
+use crate::common::{Board, Direction, SearchStats, Tile, WILDCARD};
+use crate::{SolutionInfo, Solver};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+const SIZE: usize = 3;
+const CELLS: usize = SIZE * SIZE;
+// 9! permutations, half of them reachable from any goal
+const PERMUTATIONS: usize = 362_880;
+const UNREACHED: u8 = u8::MAX;
+// Tables kept by `DistanceTable::shared`, oldest dropped first
+const SHARED_TABLES: usize = 8;
+
+// Lehmer code of a permutation of 0..9, a dense index in 0..9!
+fn rank(tiles: &[Tile]) -> usize {
+    let mut rank = 0;
+    for (i, &tile) in tiles.iter().enumerate() {
+        let smaller_after = tiles[i + 1..].iter().filter(|&&t| t < tile).count();
+        rank = rank * (CELLS - i) + smaller_after;
+    }
+    rank
+}
+
+// Calls `visit` with every way of placing `leftover` on the `free` cells
+fn fill_wildcards(
+    tiles: &mut [Tile; CELLS],
+    free: &[usize],
+    leftover: &mut [Tile],
+    visit: &mut impl FnMut(&[Tile; CELLS]),
+) {
+    let Some((&pos, rest)) = free.split_first() else {
+        visit(tiles);
+        return;
+    };
+    for i in 0..leftover.len() {
+        leftover.swap(0, i);
+        tiles[pos] = leftover[0];
+        fill_wildcards(tiles, rest, &mut leftover[1..], visit);
+        leftover.swap(0, i);
+    }
+}
+
+/// Exact move counts to the goal for every 3x3 state, filled in by one
+/// breadth-first sweep out from the goal and indexed by permutation rank.
+pub struct DistanceTable {
+    goals: Vec<Vec<Tile>>,
+    blocked: Vec<bool>,
+    distances: Vec<u8>,
+}
+
+impl DistanceTable {
+    /// Enumerates the 181,440 states reachable from the goal of a 3x3 board.
+    /// Several goals, or a goal with wildcards, are searched from all of their
+    /// completions at once.
+    pub fn build(board: &Board) -> Self {
+        assert_eq!(board.get_size(), SIZE, "distance tables cover 3x3 boards");
+        let goals = board.goal_states().to_vec();
+        let blocked: Vec<bool> = (0..CELLS).map(|pos| board.is_blocked(pos)).collect();
+        let mut distances = vec![UNREACHED; PERMUTATIONS];
+        let mut queue = VecDeque::new();
+
+        for goal in &goals {
+            let mut start = [0; CELLS];
+            start.copy_from_slice(goal);
+            // Blocked wildcard cells can only ever hold the tile glued there
+            for pos in (0..CELLS).filter(|&pos| blocked[pos] && goal[pos] == WILDCARD) {
+                start[pos] = board.tiles()[pos];
+            }
+            let free: Vec<usize> = (0..CELLS).filter(|&pos| start[pos] == WILDCARD).collect();
+            let mut leftover: Vec<Tile> =
+                (0..CELLS as Tile).filter(|t| !start.contains(t)).collect();
+            fill_wildcards(&mut start, &free, &mut leftover, &mut |tiles| {
+                let slot = &mut distances[rank(tiles)];
+                if *slot == UNREACHED {
+                    *slot = 0;
+                    queue.push_back(*tiles);
+                }
+            });
+        }
+
+        while let Some(tiles) = queue.pop_front() {
+            let distance = distances[rank(&tiles)];
+            let blank = tiles.iter().position(|&t| t == 0).unwrap();
+            let (row, col) = (blank / SIZE, blank % SIZE);
+            let neighbours = [
+                (row > 0).then(|| blank - SIZE),
+                (row + 1 < SIZE).then(|| blank + SIZE),
+                (col > 0).then(|| blank - 1),
+                (col + 1 < SIZE).then(|| blank + 1),
+            ];
+
+            for next in neighbours
+                .into_iter()
+                .flatten()
+                .filter(|&next| !blocked[next])
+            {
+                let mut child = tiles;
+                child.swap(blank, next);
+                let slot = &mut distances[rank(&child)];
+                if *slot == UNREACHED {
+                    *slot = distance + 1;
+                    queue.push_back(child);
+                }
+            }
+        }
+
+        DistanceTable {
+            goals,
+            blocked,
+            distances,
+        }
+    }
+
+    /// The process-wide table for the goals and blocked cells of `board`,
+    /// built on first use. Solvers run by name share it, so a batch of
+    /// puzzles with the same goal pays for one sweep; threads that race to
+    /// build the same table keep the first one stored.
+    pub fn shared(board: &Board) -> Arc<DistanceTable> {
+        static TABLES: Mutex<Vec<Arc<DistanceTable>>> = Mutex::new(Vec::new());
+        let cached = |tables: &[Arc<DistanceTable>]| {
+            tables
+                .iter()
+                .find(|table| table.covers(board))
+                .map(Arc::clone)
+        };
+        if let Some(table) = cached(&TABLES.lock().unwrap()) {
+            return table;
+        }
+
+        // Built unlocked so that threads after other goals need not wait
+        let built = Arc::new(DistanceTable::build(board));
+        let mut tables = TABLES.lock().unwrap();
+        // Another thread may have built the same table meanwhile
+        if let Some(table) = cached(&tables) {
+            return table;
+        }
+        if tables.len() == SHARED_TABLES {
+            tables.remove(0);
+        }
+        tables.push(Arc::clone(&built));
+        built
+    }
+
+    /// Whether the table was built for the goals and blocked cells of `board`.
+    pub fn covers(&self, board: &Board) -> bool {
+        board.get_size() == SIZE
+            && board.goal_states() == self.goals
+            && (0..CELLS).all(|pos| board.is_blocked(pos) == self.blocked[pos])
+    }
+
+    /// Optimal move count from `board` to the goal, `None` if unreachable.
+    pub fn distance(&self, board: &Board) -> Option<u8> {
+        if !self.covers(board) {
+            return None;
+        }
+        let distance = self.distances[rank(board.tiles())];
+        (distance != UNREACHED).then_some(distance)
+    }
+
+    /// Number of states at each distance from the goal.
+    pub fn histogram(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.max_distance() as usize + 1];
+        for &distance in self.distances.iter().filter(|&&d| d != UNREACHED) {
+            counts[distance as usize] += 1;
+        }
+        counts
+    }
+
+    /// Largest optimal solution length of any state (God's number).
+    pub fn max_distance(&self) -> u8 {
+        self.distances
+            .iter()
+            .copied()
+            .filter(|&d| d != UNREACHED)
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn reachable_states(&self) -> usize {
+        self.distances.iter().filter(|&&d| d != UNREACHED).count()
+    }
+}
+
+/// Optimal 3x3 solver that walks down a precomputed `DistanceTable`, taking
+/// any move that lowers the distance by one.
+pub struct TableSolver {
+    initial_board: Board,
+    table: Option<Arc<DistanceTable>>,
+}
+
+impl TableSolver {
+    /// Reuses an existing table instead of building one per solve.
+    pub fn with_table(mut self, table: Arc<DistanceTable>) -> Self {
+        self.table = Some(table);
+        self
+    }
+}
+
+impl Solver for TableSolver {
+    fn new(initial: Board) -> Self {
+        TableSolver {
+            initial_board: initial,
+            table: None,
+        }
+    }
+
+    fn new_with_goal(initial: Board) -> Self {
+        TableSolver {
+            initial_board: initial,
+            table: None,
+        }
+    }
+
+    fn solve(&self, optimal_length: Option<usize>) -> Option<SolutionInfo> {
+        if self.initial_board.get_size() != SIZE {
+            return None;
+        }
+        let table = match &self.table {
+            Some(table) if table.covers(&self.initial_board) => Arc::clone(table),
+            _ => Arc::new(DistanceTable::build(&self.initial_board)),
+        };
+
+        let mut board = self.initial_board.clone();
+        let mut distance = table.distance(&board)?;
+        let mut path: Vec<Direction> = Vec::with_capacity(distance as usize);
+        let mut stats = SearchStats::default();
+
+        while distance > 0 {
+            stats.nodes_expanded += 1;
+            let step = board.get_possible_moves().into_iter().find_map(|dir| {
+                let mut next = board.clone();
+                next.make_move(dir).unwrap();
+                stats.nodes_generated += 1;
+                (table.distance(&next) == Some(distance - 1)).then_some((dir, next))
+            });
+            let (dir, next) = step.expect("some move always leads one step closer");
+            path.push(dir);
+            board = next;
+            distance -= 1;
+        }
+
+        let cost = self.initial_board.path_cost(&path);
+        let goal = self.initial_board.goal_reached_by(&path);
+        Some(
+            SolutionInfo::new(path, optimal_length)
+                .with_cost(cost)
+                .with_goal(goal)
+                .with_stats(stats),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AStarSolver;
+
+    fn standard_table() -> Arc<DistanceTable> {
+        let goal = Board::new(vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 0]]);
+        Arc::new(DistanceTable::build(&goal))
+    }
+
+    #[test]
+    fn test_full_enumeration() {
+        let table = standard_table();
+        let histogram = table.histogram();
+
+        assert_eq!(table.reachable_states(), 181_440);
+        assert_eq!(table.max_distance(), 31);
+        assert_eq!(histogram.iter().sum::<usize>(), 181_440);
+        assert_eq!(histogram[..3], [1, 2, 4]);
+        assert_eq!(histogram[31], 2);
+    }
+
+    #[test]
+    fn test_matches_astar_length() {
+        let table = standard_table();
+        let board = Board::new(vec![vec![8, 6, 7], vec![2, 5, 4], vec![3, 0, 1]]);
+        let astar = AStarSolver::new(board.clone()).solve(None).unwrap();
+        let solution = TableSolver::new(board.clone())
+            .with_table(table)
+            .solve(None)
+            .unwrap();
+
+        assert_eq!(solution.moves.len(), astar.moves.len());
+        let mut test_board = board;
+        for dir in solution.moves {
+            test_board.make_move(dir).unwrap();
+        }
+        assert!(test_board.is_goal());
+    }
+
+    #[test]
+    fn test_shared_table_is_built_once_per_goal() {
+        let board = Board::new(vec![vec![1, 2, 3], vec![4, 0, 6], vec![7, 5, 8]]);
+        let table = DistanceTable::shared(&board);
+        assert!(Arc::ptr_eq(&table, &DistanceTable::shared(&board)));
+
+        let snail = Board::new_with_goal(
+            vec![vec![1, 2, 3], vec![4, 0, 6], vec![7, 5, 8]],
+            Board::snail_goal(3),
+        );
+        let other = DistanceTable::shared(&snail);
+        assert!(!Arc::ptr_eq(&table, &other));
+        assert!(other.covers(&snail));
+    }
+
+    #[test]
+    fn test_unsolvable_returns_none() {
+        let board = Board::new(vec![vec![2, 1, 3], vec![4, 5, 6], vec![7, 8, 0]]);
+        let solution = TableSolver::new(board)
+            .with_table(standard_table())
+            .solve(None);
+        assert!(solution.is_none());
+    }
+
+    #[test]
+    fn test_wildcard_goal_seeds_every_completion() {
+        // Fixing only the blank leaves every tile arrangement of the right parity
+        let goal = vec![vec![-1, -1, -1], vec![-1, -1, -1], vec![-1, -1, 0]];
+        let board = Board::new_with_goal(vec![vec![8, 6, 7], vec![2, 5, 4], vec![3, 0, 1]], goal);
+        let table = DistanceTable::build(&board);
+
+        assert_eq!(table.reachable_states(), 362_880);
+        assert_eq!(table.distance(&board), Some(1));
+    }
+
+    #[test]
+    fn test_several_goals_take_the_nearest() {
+        let initial_state = vec![vec![8, 6, 7], vec![2, 5, 4], vec![3, 0, 1]];
+        let standard = vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 0]];
+        let blank_first = vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7, 8]];
+        let distance_to = |goals: Vec<Vec<Vec<i32>>>| {
+            let board = Board::new_with_goals(initial_state.clone(), goals);
+            DistanceTable::build(&board).distance(&board)
+        };
+
+        let both = distance_to(vec![standard.clone(), blank_first.clone()]);
+        let nearest = distance_to(vec![standard]).min(distance_to(vec![blank_first]));
+        assert_eq!(both, nearest);
+    }
+
+    #[test]
+    fn test_blocked_centre_leaves_a_ring() {
+        // With 5 glued in the middle the others can only rotate around it
+        let mask = vec![
+            vec![false, false, false],
+            vec![false, true, false],
+            vec![false, false, false],
+        ];
+        let board = Board::new(vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 0, 8]])
+            .with_blocked_cells(mask.clone());
+        let table = DistanceTable::build(&board);
+        assert_eq!(table.reachable_states(), 8 * 7);
+        assert_eq!(table.distance(&board), Some(1));
+
+        // An even permutation that plain parity would accept
+        let cycled = Board::new(vec![vec![2, 3, 1], vec![4, 5, 6], vec![7, 8, 0]]);
+        assert!(cycled.is_solvable());
+        let cycled = cycled.with_blocked_cells(mask);
+        assert!(!cycled.is_solvable());
+        assert!(AStarSolver::new(cycled).solve(None).is_none());
+    }
+}
@@ -0,0 +1,171 @@
+use crate::common::{Board, Direction, SearchLimits, SearchStats, Tile};
+use crate::{SolutionInfo, Solver};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::time::Instant;
+
+struct State {
+    board: Board,
+    g_cost: u32, // Total move cost from the start
+}
+
+impl Ord for State {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Cheapest first
+        other.g_cost.cmp(&self.g_cost)
+    }
+}
+
+impl PartialOrd for State {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for State {
+    fn eq(&self, other: &Self) -> bool {
+        self.g_cost == other.g_cost
+    }
+}
+
+impl Eq for State {}
+
+/// Uniform-cost search (Dijkstra): expands states in order of path cost, so it
+/// finds the cheapest solution under any non-negative move costs.
+pub struct UniformCostSolver {
+    initial_board: Board,
+    limits: SearchLimits,
+}
+
+impl UniformCostSolver {
+    pub fn with_limits(mut self, limits: SearchLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+}
+
+impl Solver for UniformCostSolver {
+    fn new(initial: Board) -> Self {
+        UniformCostSolver {
+            initial_board: initial,
+            limits: SearchLimits::default(),
+        }
+    }
+
+    fn new_with_goal(initial: Board) -> Self {
+        UniformCostSolver {
+            initial_board: initial,
+            limits: SearchLimits::default(),
+        }
+    }
+
+    fn solve(&self, optimal_length: Option<usize>) -> Option<SolutionInfo> {
+        let started = Instant::now();
+        let mut stats = SearchStats::default();
+        let mut open = BinaryHeap::new();
+        // Cheapest known cost of each state and the move that reached it
+        let mut best: HashMap<Vec<Tile>, (u32, Option<Direction>)> = HashMap::new();
+
+        best.insert(self.initial_board.tiles().to_vec(), (0, None));
+        open.push(State {
+            board: self.initial_board.clone(),
+            g_cost: 0,
+        });
+
+        while let Some(current) = open.pop() {
+            let tiles = current.board.tiles().to_vec();
+            if best[&tiles].0 < current.g_cost {
+                continue; // Stale entry
+            }
+
+            if current.board.is_goal() {
+                let goal = current.board.reached_goal();
+                // Walk back by undoing the recorded moves
+                let mut path = Vec::new();
+                let mut board = current.board;
+                while let (_, Some(dir)) = best[board.tiles()] {
+                    path.push(dir);
+                    board.make_move(dir.opposite()).unwrap();
+                }
+                path.reverse();
+                return Some(
+                    SolutionInfo::new(path, optimal_length)
+                        .with_cost(current.g_cost)
+                        .with_goal(goal)
+                        .with_stats(stats),
+                );
+            }
+
+            stats.nodes_expanded += 1;
+            if self.limits.exceeded(stats.nodes_expanded, started) {
+                return None;
+            }
+
+            for dir in current.board.get_possible_moves() {
+                let Some(move_cost) = current.board.cost_of_move(dir) else {
+                    continue;
+                };
+                let mut board = current.board.clone();
+                board.make_move(dir).unwrap();
+                stats.nodes_generated += 1;
+
+                let g_cost = current.g_cost + move_cost;
+                let known = best.get(board.tiles()).map(|&(cost, _)| cost);
+                if known.is_none_or(|cost| g_cost < cost) {
+                    best.insert(board.tiles().to_vec(), (g_cost, Some(dir)));
+                    open.push(State { board, g_cost });
+                }
+            }
+            stats.max_frontier = stats.max_frontier.max(open.len());
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{Heuristic, MoveCost};
+    use crate::{AStarSolver, IDAStarSolver, RBFSSolver};
+
+    #[test]
+    fn test_unit_costs_match_move_count() {
+        let board = Board::new(vec![vec![1, 2, 3], vec![4, 0, 6], vec![7, 5, 8]]);
+        let solution = UniformCostSolver::new(board).solve(None).unwrap();
+        assert_eq!(solution.moves.len(), 2);
+        assert_eq!(solution.total_cost, 2);
+    }
+
+    #[test]
+    fn test_face_value_costs_agree_with_cost_aware_solvers() {
+        let board = Board::new(vec![vec![4, 1, 3], vec![7, 2, 6], vec![0, 5, 8]])
+            .with_move_cost(MoveCost::FaceValue);
+
+        let ucs = UniformCostSolver::new(board.clone()).solve(None).unwrap();
+        let astar = AStarSolver::new(board.clone())
+            .with_heuristic(Heuristic::WeightedManhattan)
+            .solve(None)
+            .unwrap();
+        let ida = IDAStarSolver::new(board.clone())
+            .with_heuristic(Heuristic::WeightedManhattan)
+            .solve(None)
+            .unwrap();
+
+        assert_eq!(board.path_cost(&ucs.moves), ucs.total_cost);
+        assert_eq!(astar.total_cost, ucs.total_cost);
+        assert_eq!(ida.total_cost, ucs.total_cost);
+        assert!(board.heuristic(Heuristic::WeightedManhattan) <= ucs.total_cost);
+    }
+
+    #[test]
+    fn test_cheapest_solution_can_be_longer() {
+        let board = Board::new(vec![vec![0, 1, 2], vec![3, 8, 5], vec![4, 7, 6]])
+            .with_move_cost(MoveCost::FaceValue);
+
+        let shortest = RBFSSolver::new(board.clone()).solve(None).unwrap();
+        let cheapest = UniformCostSolver::new(board).solve(None).unwrap();
+        assert!(cheapest.moves.len() > shortest.moves.len());
+        assert!(cheapest.total_cost < shortest.total_cost);
+    }
+}
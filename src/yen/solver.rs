@@ -0,0 +1,279 @@
+use crate::common::{Board, Direction, Heuristic, SearchStats, Tile};
+use crate::{SolutionInfo, Solver};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+struct State {
+    board: Board,
+    g_cost: u32,
+    f_cost: u32,
+}
+
+impl Ord for State {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Lowest f first, deeper nodes first on ties
+        other
+            .f_cost
+            .cmp(&self.f_cost)
+            .then_with(|| self.g_cost.cmp(&other.g_cost))
+    }
+}
+
+impl PartialOrd for State {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for State {
+    fn eq(&self, other: &Self) -> bool {
+        self.f_cost == other.f_cost && self.g_cost == other.g_cost
+    }
+}
+
+impl Eq for State {}
+
+/// Yen's algorithm over the puzzle graph: the k cheapest loop-free solutions,
+/// each found by deviating from an earlier one at some state along it.
+pub struct YenSolver {
+    initial_board: Board,
+    heuristic: Heuristic,
+    k: usize,
+}
+
+impl YenSolver {
+    pub fn with_heuristic(mut self, heuristic: Heuristic) -> Self {
+        self.heuristic = heuristic;
+        self
+    }
+
+    pub fn with_k(mut self, k: usize) -> Self {
+        self.k = k;
+        self
+    }
+
+    /// Up to `k` distinct loop-free solutions in order of increasing cost.
+    /// Each one is graded against the length of the first and carries the
+    /// statistics of the whole search.
+    pub fn solve_k(&self) -> Vec<SolutionInfo> {
+        let mut stats = SearchStats::default();
+        if self.k == 0 || !self.initial_board.is_solvable() {
+            return Vec::new();
+        }
+        let Some(first) = self.shortest_path(&self.initial_board, &HashSet::new(), &[], &mut stats)
+        else {
+            return Vec::new();
+        };
+
+        let mut accepted: Vec<Vec<Direction>> = vec![first];
+        let mut seen: HashSet<Vec<Direction>> = accepted.iter().cloned().collect();
+        let mut candidates: Vec<(u32, Vec<Direction>)> = Vec::new();
+
+        while accepted.len() < self.k {
+            let previous = accepted.last().unwrap().clone();
+            let mut spur = self.initial_board.clone();
+            let mut root_states: HashSet<Vec<Tile>> = HashSet::new();
+
+            for i in 0..previous.len() {
+                let root = &previous[..i];
+                // Moves already taken from this spur by solutions sharing the root
+                let banned_moves: Vec<Direction> = accepted
+                    .iter()
+                    .filter(|path| path.len() > i && path[..i] == *root)
+                    .map(|path| path[i])
+                    .collect();
+
+                if let Some(tail) =
+                    self.shortest_path(&spur, &root_states, &banned_moves, &mut stats)
+                {
+                    let path = [root, &tail].concat();
+                    if seen.insert(path.clone()) {
+                        let cost = self.initial_board.path_cost(&path);
+                        candidates.push((cost, path));
+                    }
+                }
+
+                root_states.insert(spur.tiles().to_vec());
+                spur.make_move(previous[i]).unwrap();
+            }
+
+            // Cheapest candidate next, the shorter one on equal cost
+            let next = candidates
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, (cost, path))| (*cost, path.len()))
+                .map(|(index, _)| index);
+            match next {
+                Some(index) => accepted.push(candidates.swap_remove(index).1),
+                None => break,
+            }
+        }
+
+        let optimal_length = accepted[0].len();
+        accepted
+            .into_iter()
+            .map(|path| {
+                let cost = self.initial_board.path_cost(&path);
+                let goal = self.initial_board.goal_reached_by(&path);
+                SolutionInfo::new(path, Some(optimal_length))
+                    .with_cost(cost)
+                    .with_goal(goal)
+                    .with_stats(stats.clone())
+            })
+            .collect()
+    }
+
+    // A* from `start` that never enters `banned_states` and never opens with
+    // one of `banned_moves`
+    fn shortest_path(
+        &self,
+        start: &Board,
+        banned_states: &HashSet<Vec<Tile>>,
+        banned_moves: &[Direction],
+        stats: &mut SearchStats,
+    ) -> Option<Vec<Direction>> {
+        let mut open = BinaryHeap::new();
+        // Cheapest known cost of each state and the move that reached it
+        let mut best: HashMap<Vec<Tile>, (u32, Option<Direction>)> = HashMap::new();
+
+        best.insert(start.tiles().to_vec(), (0, None));
+        open.push(State {
+            board: start.clone(),
+            g_cost: 0,
+            f_cost: start.heuristic(self.heuristic),
+        });
+
+        while let Some(current) = open.pop() {
+            if best[current.board.tiles()].0 < current.g_cost {
+                continue; // Stale entry
+            }
+
+            if current.board.is_goal() {
+                let mut path = Vec::new();
+                let mut board = current.board;
+                while let (_, Some(dir)) = best[board.tiles()] {
+                    path.push(dir);
+                    board.make_move(dir.opposite()).unwrap();
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            stats.nodes_expanded += 1;
+            let at_start = current.g_cost == 0 && current.board.tiles() == start.tiles();
+            for dir in current.board.get_possible_moves() {
+                if at_start && banned_moves.contains(&dir) {
+                    continue;
+                }
+                let Some(move_cost) = current.board.cost_of_move(dir) else {
+                    continue;
+                };
+                let mut board = current.board.clone();
+                board.make_move(dir).unwrap();
+                if banned_states.contains(board.tiles()) {
+                    continue;
+                }
+                stats.nodes_generated += 1;
+
+                let g_cost = current.g_cost + move_cost;
+                let known = best.get(board.tiles()).map(|&(cost, _)| cost);
+                if known.is_none_or(|cost| g_cost < cost) {
+                    best.insert(board.tiles().to_vec(), (g_cost, Some(dir)));
+                    let f_cost = g_cost + board.heuristic(self.heuristic);
+                    open.push(State {
+                        board,
+                        g_cost,
+                        f_cost,
+                    });
+                }
+            }
+            stats.max_frontier = stats.max_frontier.max(open.len());
+        }
+
+        None
+    }
+}
+
+impl Solver for YenSolver {
+    fn new(initial: Board) -> Self {
+        YenSolver {
+            initial_board: initial,
+            heuristic: Heuristic::default(),
+            k: 1,
+        }
+    }
+
+    fn new_with_goal(initial: Board) -> Self {
+        YenSolver {
+            initial_board: initial,
+            heuristic: Heuristic::default(),
+            k: 1,
+        }
+    }
+
+    fn solve(&self, optimal_length: Option<usize>) -> Option<SolutionInfo> {
+        let best = self.solve_k().into_iter().next()?;
+        let cost = best.total_cost;
+        Some(
+            SolutionInfo::new(best.moves, optimal_length)
+                .with_cost(cost)
+                .with_goal(best.reached_goal)
+                .with_stats(best.stats),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SolutionEnumerator, SolutionQuality};
+
+    fn visited_states(board: &Board, path: &[Direction]) -> Vec<Vec<Tile>> {
+        let mut board = board.clone();
+        let mut states = vec![board.tiles().to_vec()];
+        for &dir in path {
+            board.make_move(dir).unwrap();
+            states.push(board.tiles().to_vec());
+        }
+        assert!(board.is_goal());
+        states
+    }
+
+    #[test]
+    fn test_two_by_two_has_exactly_two_simple_paths() {
+        // The 2x2 state graph is a single cycle of 12 states
+        let board = Board::new(vec![vec![1, 0], vec![3, 2]]);
+        let solutions = YenSolver::new(board).with_k(5).solve_k();
+
+        let lengths: Vec<usize> = solutions.iter().map(|s| s.moves.len()).collect();
+        assert_eq!(lengths, vec![1, 11]);
+    }
+
+    #[test]
+    fn test_paths_are_distinct_loop_free_and_ordered() {
+        let board = Board::new(vec![vec![0, 5, 6], vec![1, 2, 3], vec![4, 7, 8]]);
+        let solutions = YenSolver::new(board.clone()).with_k(8).solve_k();
+        let optimal_count = SolutionEnumerator::new(board.clone())
+            .enumerate()
+            .unwrap()
+            .count as usize;
+
+        assert_eq!(solutions.len(), 8);
+        assert!(solutions
+            .windows(2)
+            .all(|w| w[0].total_cost <= w[1].total_cost));
+        // Exactly the optimal solutions come first
+        assert!(solutions[..optimal_count]
+            .iter()
+            .all(|s| matches!(s.quality, SolutionQuality::Optimal)));
+        assert!(solutions[optimal_count].moves.len() > solutions[0].moves.len());
+
+        let distinct: HashSet<&Vec<Direction>> = solutions.iter().map(|s| &s.moves).collect();
+        assert_eq!(distinct.len(), solutions.len());
+        for solution in &solutions {
+            let states = visited_states(&board, &solution.moves);
+            let unique: HashSet<&Vec<Tile>> = states.iter().collect();
+            assert_eq!(unique.len(), states.len());
+        }
+    }
+}